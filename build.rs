@@ -5,12 +5,18 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use bzip2::read::BzDecoder;
-use cc;
-use embed_resource;
+use sha2::{Digest, Sha256};
 
 const WX_PATH: &str = "wxWidgets-3.2.2.1";
+/// The oldest system-installed wxWidgets we can build the bridge against
+const WX_MIN_VERSION: (u32, u32) = (3, 2);
 const WX_SOURCE: &str =
     "https://github.com/wxWidgets/wxWidgets/releases/download/v3.2.2.1/wxWidgets-3.2.2.1.tar.bz2";
+/// SHA-256 of `WX_SOURCE`. The release's checksums are listed at
+/// https://github.com/wxWidgets/wxWidgets/releases/tag/v3.2.2.1, and this one can be checked
+/// with `curl -L <WX_SOURCE> | sha256sum`. Can be overridden with the `WX_SOURCE_SHA256`
+/// environment variable
+const WX_SOURCE_SHA256: &str = "dffcb6be71296fff4b7f8840eb1b510178f57aa2eb236b20da41182009242c02";
 
 fn is_windows() -> bool {
     cfg!(windows)
@@ -24,11 +30,149 @@ fn is_osx() -> bool {
     cfg!(target_os = "macos")
 }
 
+fn is_linux() -> bool {
+    cfg!(target_os = "linux")
+}
+
 fn is_stub() -> bool {
-    cfg!(feature = "stub_lib") || (!is_windows() && !is_osx())
+    cfg!(feature = "stub_lib")
+        || cfg!(feature = "mock")
+        || !(system_wx_config().is_some() || is_windows() || is_osx() || is_linux())
+}
+
+/// The `wx-config` of a system-installed wxWidgets, if we've been asked to use one
+fn system_wx_config() -> Option<PathBuf> {
+    if let Ok(wx_config) = env::var("WX_CONFIG") {
+        Some(PathBuf::from(wx_config))
+    } else if cfg!(feature = "system_wx") {
+        Some(PathBuf::from("wx-config"))
+    } else {
+        None
+    }
+}
+
+/// Where the wxWidgets sources and builds are kept
+fn cache_dir() -> PathBuf {
+    env::var("WX_RS_CACHE_DIR").map_or_else(|_| PathBuf::from("./dist/"), PathBuf::from)
+}
+
+fn is_debug() -> bool {
+    env::var("PROFILE").is_ok_and(|p| p == "debug")
+}
+
+/// Optional wxWidgets components that have been enabled, by their wxWidgets library name
+fn components() -> Vec<&'static str> {
+    let mut components = vec![];
+    if cfg!(feature = "aui") {
+        components.push("aui");
+    }
+    if cfg!(feature = "html") {
+        components.push("html");
+    }
+    if cfg!(feature = "opengl") {
+        components.push("gl");
+    }
+    if cfg!(feature = "propgrid") {
+        components.push("propgrid");
+    }
+    if cfg!(feature = "ribbon") {
+        components.push("ribbon");
+    }
+    if cfg!(feature = "richtext") {
+        components.push("richtext");
+    }
+    if cfg!(feature = "stc") {
+        components.push("stc");
+    }
+    if cfg!(feature = "xrc") {
+        components.push("xrc");
+    }
+    components
+}
+
+/// The libraries to ask `wx-config` for
+fn wx_config_lib_list() -> String {
+    let mut libs = vec!["core", "base"];
+    libs.extend(components());
+    if cfg!(feature = "xrc") || cfg!(feature = "richtext") {
+        libs.push("xml");
+    }
+    libs.join(",")
+}
+
+/// Let the bridge know which components it can use, e.g. `WXRS_USE_STC`
+fn define_components(build: &mut cc::Build) {
+    for component in components() {
+        build.define(&format!("WXRS_USE_{}", component.to_uppercase()), None);
+    }
+}
+
+/// Identifies a wxWidgets build configuration, so that several can sit side by side in the cache
+fn cache_key() -> String {
+    format!("{}-{}", source_key(), build_config())
+}
+
+/// Names the wxWidgets sources. A `WX_SOURCE_DIR` can be any tree, so it's named by its path
+fn source_key() -> String {
+    match env::var("WX_SOURCE_DIR") {
+        Ok(dir) => {
+            let path = fs::canonicalize(&dir).unwrap_or_else(|_| PathBuf::from(&dir));
+            let hash = format!("{:x}", Sha256::digest(path.to_string_lossy().as_bytes()));
+            let name = path
+                .file_name()
+                .map_or_else(|| "wxWidgets".into(), |n| n.to_string_lossy());
+            format!("{}-{}", name, &hash[..8])
+        }
+        Err(_) => WX_PATH.to_string(),
+    }
+}
+
+/// The target, build type and components, which make up the rest of the cache key
+fn build_config() -> String {
+    let components = components();
+    format!(
+        "{}-{}-{}",
+        env::var("TARGET").unwrap(),
+        if is_debug() { "debug" } else { "release" },
+        if components.is_empty() {
+            "default".to_string()
+        } else {
+            components.join("_")
+        }
+    )
+}
+
+fn has_gtk() -> bool {
+    Command::new("pkg-config")
+        .args(["--exists", "gtk+-3.0"])
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// GTK's `--cflags` or `--libs` on Linux, which the bridge needs on top of wxGTK's since it
+/// calls GTK and GDK directly. `wx-config` only gives them for static builds, if at all
+fn gtk_flags(arg: &str) -> Result<String, Box<dyn Error>> {
+    if !is_linux() {
+        return Ok(String::new());
+    }
+    wx_config_output(Path::new("pkg-config"), &[arg, "gtk+-3.0"])
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
+    println!("cargo:rerun-if-env-changed=WX_CONFIG");
+    println!("cargo:rerun-if-env-changed=WX_RS_CACHE_DIR");
+    println!("cargo:rerun-if-env-changed=WX_RS_ICON");
+    if let (Some(wx_config), false) = (system_wx_config(), is_stub()) {
+        return build_with_system_wx(&wx_config);
+    }
+    if is_linux() && !is_stub() && !has_gtk() {
+        return Err(
+            "wx-rs: GTK 3 development files not found (pkg-config can't find gtk+-3.0). \
+            Install them, e.g. libgtk-3-dev, or enable the stub_lib feature to build without them"
+                .into(),
+        );
+    }
+
     let wx_path = download_dist()?;
     build_bridge_lib(&wx_path)?;
 
@@ -36,69 +180,253 @@ fn main() -> Result<(), Box<dyn Error>> {
         return Ok(());
     }
 
-    let wx_libs = get_libs(&wx_path)?;
-
-    let mut iter = wx_libs.split_whitespace();
-    while let Some(flag) = iter.next() {
-        if flag.starts_with("-l") {
-            println!("cargo:rustc-link-lib={}", &flag[2..]);
-        } else if flag.starts_with("-L") {
-            println!("cargo:rustc-link-search={}", &flag[2..]);
-        } else if flag == "-framework" {
-            println!("cargo:rustc-link-lib=framework={}", iter.next().unwrap());
-        }
-    }
+    link_wx_libs(&get_libs(&wx_path)?);
 
     if is_msvc() {
-        println!("cargo:rustc-link-lib=static=wxbase32u");
-        println!("cargo:rustc-link-lib=static=wxmsw32u_core");
-        println!("cargo:rustc-link-lib=static=wxpng");
-        println!("cargo:rustc-link-lib=static=wxjpeg");
-        println!("cargo:rustc-link-lib=static=wxtiff");
-        println!("cargo:rustc-link-lib=static=wxregexu");
-        println!("cargo:rustc-link-lib=static=wxzlib");
+        let d = msvc_lib_suffix();
+        println!("cargo:rustc-link-lib=static=wxbase32u{}", d);
+        println!("cargo:rustc-link-lib=static=wxmsw32u{}_core", d);
+        println!("cargo:rustc-link-lib=static=wxpng{}", d);
+        println!("cargo:rustc-link-lib=static=wxjpeg{}", d);
+        println!("cargo:rustc-link-lib=static=wxtiff{}", d);
+        println!("cargo:rustc-link-lib=static=wxregexu{}", d);
+        println!("cargo:rustc-link-lib=static=wxzlib{}", d);
+        for component in components() {
+            println!("cargo:rustc-link-lib=static=wxmsw32u{}_{}", d, component);
+            match component {
+                "gl" => println!("cargo:rustc-link-lib=opengl32"),
+                "stc" => println!("cargo:rustc-link-lib=static=wxscintilla{}", d),
+                "xrc" | "richtext" => {
+                    println!("cargo:rustc-link-lib=static=wxbase32u{}_xml", d);
+                    println!("cargo:rustc-link-lib=static=wxexpat{}", d);
+                }
+                _ => (),
+            }
+        }
         let include_path = wx_path.join("include");
-        let resource_file = include_path.join("wx/msw/wx.rc");
         env::set_var("INCLUDE", include_path.to_str().unwrap());
-        embed_resource::compile(resource_file);
+        embed_resource::compile(resource_script(&wx_path)?);
     } else if is_windows() {
         println!("cargo:rustc-link-search=C://msys64/mingw64/lib");
         println!("cargo:rustc-link-search=C://msys64/usr/lib/w32api");
-        println!("cargo:rustc-link-lib=static=wx_mswu_gl-3.2");
+        // Components come before the libraries they use, for the static linker
+        for component in components() {
+            println!("cargo:rustc-link-lib=static=wx_mswu_{}-3.2", component);
+        }
         println!("cargo:rustc-link-lib=static=wx_mswu_core-3.2");
+        if cfg!(feature = "xrc") || cfg!(feature = "richtext") {
+            println!("cargo:rustc-link-lib=static=wx_baseu_xml-3.2");
+        }
         println!("cargo:rustc-link-lib=static=wx_baseu-3.2");
         println!("cargo:rustc-link-lib=static=wxpng-3.2");
         println!("cargo:rustc-link-lib=static=wxjpeg-3.2");
         println!("cargo:rustc-link-lib=static=wxtiff-3.2");
         println!("cargo:rustc-link-lib=static=wxregexu-3.2");
-        embed_resource_file(&wx_path);
+        for component in components() {
+            match component {
+                "gl" => println!("cargo:rustc-link-lib=opengl32"),
+                "stc" => println!("cargo:rustc-link-lib=static=wxscintilla-3.2"),
+                _ => (),
+            }
+        }
+        if cfg!(feature = "xrc") || cfg!(feature = "richtext") {
+            println!("cargo:rustc-link-lib=static=wxexpat-3.2");
+        }
+        embed_resource_file(&wx_path)?;
+    }
+
+    Ok(())
+}
+
+fn build_with_system_wx(wx_config: &Path) -> Result<(), Box<dyn Error>> {
+    let version = String::from_utf8(
+        Command::new(wx_config)
+            .arg("--version")
+            .output()
+            .map_err(|e| format!("wx-rs: Couldn't run \"{}\": {}", wx_config.display(), e))?
+            .stdout,
+    )?;
+    let version = version.trim();
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().unwrap_or(0));
+    let major_minor = (parts.next().unwrap_or(0), parts.next().unwrap_or(0));
+    if major_minor < WX_MIN_VERSION {
+        return Err(format!(
+            "wx-rs: \"{}\" reports wxWidgets {}, but at least {}.{} is required",
+            wx_config.display(),
+            version,
+            WX_MIN_VERSION.0,
+            WX_MIN_VERSION.1
+        )
+        .into());
     }
+    println!(
+        "cargo:warning=wx-rs: Using system wxWidgets {} from \"{}\"",
+        version,
+        wx_config.display()
+    );
+
+    println!("cargo:rerun-if-changed=cpp_src/wxbridge.cpp");
+    env::set_var(
+        "CXXFLAGS",
+        format!(
+            "{} {}",
+            wx_config_output(wx_config, &["--cxxflags"])?,
+            gtk_flags("--cflags")?
+        ),
+    );
+    let mut build = cc::Build::new();
+    define_components(&mut build);
+    build
+        .cpp(true)
+        .file("cpp_src/wxbridge.cpp")
+        .shared_flag(false)
+        .compile("libwxbridge.a");
+
+    link_wx_libs(&format!(
+        "{} {}",
+        wx_config_output(wx_config, &["--libs", &wx_config_lib_list()])?,
+        gtk_flags("--libs")?
+    ));
 
     Ok(())
 }
 
+fn link_wx_libs(wx_libs: &str) {
+    let mut iter = wx_libs.split_whitespace();
+    while let Some(flag) = iter.next() {
+        if let Some(lib) = flag.strip_prefix("-l") {
+            println!("cargo:rustc-link-lib={}", lib);
+        } else if let Some(path) = flag.strip_prefix("-L") {
+            println!("cargo:rustc-link-search={}", path);
+        } else if flag == "-framework" {
+            println!("cargo:rustc-link-lib=framework={}", iter.next().unwrap());
+        } else if flag.ends_with(".a") {
+            // Static builds of wx-config list their archives by full path
+            let path = Path::new(flag);
+            let name = path.file_stem().unwrap().to_str().unwrap();
+            println!(
+                "cargo:rustc-link-search={}",
+                path.parent().unwrap().display()
+            );
+            println!(
+                "cargo:rustc-link-lib=static={}",
+                name.strip_prefix("lib").unwrap_or(name)
+            );
+        }
+    }
+}
+
 fn download_dist() -> Result<PathBuf, Box<dyn Error>> {
-    let dist_path = Path::new("./dist/");
-    let wx_path = PathBuf::from(format!("./dist/{}", WX_PATH));
-    if wx_path.exists() || is_stub() {
+    println!("cargo:rerun-if-env-changed=WX_SOURCE_DIR");
+    println!("cargo:rerun-if-env-changed=WX_SOURCE_ARCHIVE");
+    println!("cargo:rerun-if-env-changed=WX_SOURCE_SHA256");
+    if is_stub() {
+        return Ok(cache_dir().join(WX_PATH));
+    }
+    if let Ok(source_dir) = env::var("WX_SOURCE_DIR") {
+        let wx_path = PathBuf::from(source_dir);
+        if !wx_path.join("include").join("wx").exists() {
+            return Err(format!(
+                "wx-rs: WX_SOURCE_DIR \"{}\" is not a wxWidgets source tree",
+                wx_path.display()
+            )
+            .into());
+        }
         return Ok(wx_path);
     }
-    println!("cargo:warning=wx-rs: Downloading source to './dist/");
-    fs::create_dir_all(dist_path)?;
-    let source_response = reqwest::blocking::get(WX_SOURCE)?;
-    let decoder = BzDecoder::new(source_response);
+
+    let dist_path = cache_dir();
+    let wx_path = dist_path.join(WX_PATH);
+    // The hash of the archive that was unpacked, to compare a later WX_SOURCE_ARCHIVE with
+    let unpacked_hash_path = dist_path.join(format!("{}.sha256", WX_PATH));
+    if wx_path.exists() {
+        if let Ok(source_archive) = env::var("WX_SOURCE_ARCHIVE") {
+            let hash = verify_archive(&read_archive(&source_archive)?)?;
+            let Ok(unpacked_hash) = fs::read_to_string(&unpacked_hash_path) else {
+                // Unpacked before hashes were kept, or copied in by hand
+                println!(
+                    "cargo:warning=wx-rs: Can't tell which archive '{}' was unpacked from. \
+                     Assuming it was WX_SOURCE_ARCHIVE \"{}\"",
+                    wx_path.display(),
+                    source_archive
+                );
+                fs::write(&unpacked_hash_path, &hash)?;
+                return Ok(wx_path);
+            };
+            if unpacked_hash.trim() != hash {
+                return Err(format!(
+                    "wx-rs: WX_SOURCE_ARCHIVE \"{}\" isn't the archive that '{}' was unpacked \
+                     from. Remove '{}' to unpack it again",
+                    source_archive,
+                    wx_path.display(),
+                    wx_path.display()
+                )
+                .into());
+            }
+        }
+        return Ok(wx_path);
+    }
+    let archive = if let Ok(source_archive) = env::var("WX_SOURCE_ARCHIVE") {
+        println!(
+            "cargo:warning=wx-rs: Unpacking {} to '{}'",
+            source_archive,
+            dist_path.display()
+        );
+        read_archive(&source_archive)?
+    } else {
+        println!(
+            "cargo:warning=wx-rs: Downloading source to '{}'",
+            dist_path.display()
+        );
+        reqwest::blocking::get(WX_SOURCE)?
+            .error_for_status()?
+            .bytes()?
+            .to_vec()
+    };
+    let hash = verify_archive(&archive)?;
+
+    fs::create_dir_all(&dist_path)?;
+    let decoder = BzDecoder::new(archive.as_slice());
     let mut archive = tar::Archive::new(decoder);
     for file in archive.entries()? {
         let mut file = file?;
-        file.unpack_in(dist_path)?;
+        file.unpack_in(&dist_path)?;
+    }
+    if !wx_path.exists() {
+        return Err(format!("wx-rs: The wxWidgets archive didn't contain {}", WX_PATH).into());
     }
+    fs::write(&unpacked_hash_path, &hash)?;
+
+    Ok(wx_path)
+}
+
+fn read_archive(source_archive: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    Ok(fs::read(source_archive)
+        .map_err(|e| format!("wx-rs: Couldn't read \"{}\": {}", source_archive, e))?)
+}
 
-    return Ok(wx_path);
+/// Check the archive against the pinned SHA-256, and return its hash
+fn verify_archive(archive: &[u8]) -> Result<String, Box<dyn Error>> {
+    let hash = format!("{:x}", Sha256::digest(archive));
+    let expected = env::var("WX_SOURCE_SHA256").unwrap_or_else(|_| WX_SOURCE_SHA256.to_string());
+    if !expected.trim().eq_ignore_ascii_case(&hash) {
+        return Err(format!(
+            "wx-rs: SHA-256 mismatch for the wxWidgets archive. Expected {}, got {}",
+            expected.trim(),
+            hash
+        )
+        .into());
+    }
+    Ok(hash)
 }
 
 fn build_bridge_lib(wx_path: &Path) -> Result<(), Box<dyn Error>> {
     if is_stub() {
-        println!("cargo:warning=wx-rs: Platform unsupported. Building a stub library.");
+        if cfg!(feature = "mock") {
+            println!("cargo:warning=wx-rs: Using the mock backend. Building a stub library.");
+        } else {
+            println!("cargo:warning=wx-rs: Platform unsupported. Building a stub library.");
+        }
         println!("cargo:rerun-if-changed=cpp_src/wxstub.cpp");
         cc::Build::new()
             .cpp(true)
@@ -111,32 +439,40 @@ fn build_bridge_lib(wx_path: &Path) -> Result<(), Box<dyn Error>> {
     //panic!("{}", wx_path.to_str().unwrap());
     if is_windows() {
         build_msvc(wx_path)?;
+    } else if is_linux() {
+        build_gtk(wx_path)?;
     } else {
         panic!("Not supported")
     };
 
     let wx_flags = if is_msvc() {
         format!(
-            "-I{}/lib/vc_x64_lib/mswu -I{}/include -D_WIN64 -D_FILE_OFFSET_BITS=64 -D__WXMSW__ -D_UNICODE -DNDEBUG -DNOPCH  /GR /EHsc",
-            wx_path.display(),
+            "-I{}/mswu{} -I{}/include -D_WIN64 -D_FILE_OFFSET_BITS=64 -D__WXMSW__ -D_UNICODE {} -DNOPCH  /GR /EHsc",
+            msvc_lib_dir(wx_path).display(),
+            msvc_lib_suffix(),
             wx_path.display(),
+            if is_debug() { "" } else { "-DNDEBUG" },
         )
     } else if is_windows() {
         format!("-I{}/msw64-release-build/lib/wx/include/msw-unicode-static-3.1 -I{}/include -D_FILE_OFFSET_BITS=64 -D__WXMSW__", wx_path.display(), wx_path.display())
-    } else if is_osx() {
-        String::from_utf8(
-            Command::new(format!("{}/osx-release-build/wx-config", wx_path.display()))
-                .arg("--cxxflags")
-                .output()?
-                .stdout,
-        )?
+    } else if is_osx() || is_linux() {
+        format!(
+            "{} {}",
+            wx_config_output(&wx_config(wx_path), &["--cxxflags"])?,
+            gtk_flags("--cflags")?
+        )
     } else {
         panic!("Unsupported platform")
     };
 
     env::set_var("CXXFLAGS", wx_flags);
 
-    cc::Build::new()
+    let mut build = cc::Build::new();
+    define_components(&mut build);
+    if is_windows() && embedded_icon().is_some() {
+        build.define("WXRS_ICON", None);
+    }
+    build
         .cpp(true)
         .file("cpp_src/wxbridge.cpp")
         .shared_flag(false)
@@ -145,52 +481,198 @@ fn build_bridge_lib(wx_path: &Path) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn build_msvc(wx_path: &Path) -> Result<(), Box<dyn Error>> {
-    if wx_path
+/// An `.ico` to embed in Windows executables, which becomes their icon and the default icon of
+/// their windows. Given as an absolute path, since the build script runs from wx-rs' directory
+fn embedded_icon() -> Option<PathBuf> {
+    env::var("WX_RS_ICON").ok().map(PathBuf::from)
+}
+
+/// The resource script to compile on Windows: wxWidgets' own, with the icon from `WX_RS_ICON`
+/// added in front of it when there is one
+fn resource_script(wx_path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let wx_resources = wx_path.join("include/wx/msw/wx.rc");
+    let icon = match embedded_icon() {
+        Some(icon) => icon,
+        None => return Ok(wx_resources),
+    };
+    if !icon.is_file() {
+        return Err(format!("wx-rs: WX_RS_ICON \"{}\" isn't a file", icon.display()).into());
+    }
+    println!("cargo:rerun-if-changed={}", icon.display());
+
+    // Windows uses the first icon, by name, as the executable's. The bridge loads it by name too
+    let script = PathBuf::from(env::var("OUT_DIR")?).join("wxrs.rc");
+    fs::write(
+        &script,
+        format!(
+            "aaaa ICON \"{}\"\n#include \"wx/msw/wx.rc\"\n",
+            icon.display().to_string().replace('\\', "/")
+        ),
+    )?;
+    Ok(script)
+}
+
+/// nmake's `CFG` suffix, which keeps each configuration's output in its own directory
+fn msvc_cfg() -> String {
+    // nmake builds inside the source tree, so the sources needn't be part of the name
+    format!("-{}", build_config())
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn msvc_lib_dir(wx_path: &Path) -> PathBuf {
+    wx_path
         .join("lib")
-        .join("vc_x64_lib")
-        .join("wxmsw32u_core.lib")
+        .join(format!("vc_x64_lib{}", msvc_cfg()))
+}
+
+/// Debug builds of wxWidgets have a `d` appended to their library names
+fn msvc_lib_suffix() -> &'static str {
+    if is_debug() {
+        "d"
+    } else {
+        ""
+    }
+}
+
+fn build_msvc(wx_path: &Path) -> Result<(), Box<dyn Error>> {
+    if msvc_lib_dir(wx_path)
+        .join(format!("wxmsw32u{}_core.lib", msvc_lib_suffix()))
         .exists()
     {
         println!("cargo:warning=wx-rs: Already built wxWidgets. Skipping a re-build.");
         return Ok(());
     }
     println!("cargo:warning=wx-rs: Building wxWidgets. This can take a few minutes.");
+    let cfg = format!("CFG={}", msvc_cfg());
+    let components = components();
+    let use_component = |component: &str, switch: &str| {
+        format!("{}={}", switch, components.contains(&component) as u8)
+    };
     let status = Command::new("nmake")
         .current_dir(wx_path.join("build").join("msw"))
         .args([
             "-f",
             "makefile.vc",
             "-a",
-            "BUILD=release",
+            if is_debug() {
+                "BUILD=debug"
+            } else {
+                "BUILD=release"
+            },
+            // Rust always links against the release CRT
+            "DEBUG_RUNTIME_LIBS=0",
+            &cfg,
             "TARGET_CPU=X64",
-            "USE_STC=0",
-            "USE_OPENGL=0",
-            "USE_HTML=0",
-            "USE_AUI=0",
+            &use_component("stc", "USE_STC"),
+            &use_component("gl", "USE_OPENGL"),
+            &use_component("html", "USE_HTML"),
+            &use_component("aui", "USE_AUI"),
             "USE_MEDIA=0",
-            "USE_PROPGRID=0",
+            &use_component("propgrid", "USE_PROPGRID"),
             "USE_QA=0",
-            "USE_RIBBON=0",
+            &use_component("ribbon", "USE_RIBBON"),
+            &use_component("richtext", "USE_RICHTEXT"),
             "USE_WEBVIEW=0",
-            "USE_XRC=0",
+            &use_component("xrc", "USE_XRC"),
+        ])
+        .output()?;
+    let stdout = std::str::from_utf8(&status.stdout)?.to_string();
+    let stderr = std::str::from_utf8(&status.stderr)?.to_string();
+    println!("Building: {}", stdout);
+    assert!(status.status.success(), "{}", stderr);
+
+    Ok(())
+}
+
+fn build_gtk(wx_path: &Path) -> Result<(), Box<dyn Error>> {
+    let build_path = gtk_build_dir();
+    // configure alone writes wx-config and lib/, so only a finished make counts as built
+    let built_stamp = build_path.join("wxrs-built");
+    if built_stamp.exists() {
+        println!("cargo:warning=wx-rs: Already built wxWidgets. Skipping a re-build.");
+        return Ok(());
+    }
+    println!("cargo:warning=wx-rs: Building wxWidgets. This can take a few minutes.");
+    fs::create_dir_all(&build_path)?;
+    let components = components();
+    let enable_component = |component: &str, option: &str| {
+        let enabled = components.contains(&component);
+        match option {
+            "opengl" if enabled => "--with-opengl".to_string(),
+            "opengl" => "--without-opengl".to_string(),
+            _ if enabled => format!("--enable-{}", option),
+            _ => format!("--disable-{}", option),
+        }
+    };
+    let status = Command::new(wx_path.canonicalize()?.join("configure"))
+        .current_dir(&build_path)
+        .args([
+            if is_debug() {
+                "--enable-debug"
+            } else {
+                "--disable-debug_info"
+            },
+            "--with-gtk=3",
+            "--disable-shared",
+            &enable_component("gl", "opengl"),
+            &enable_component("stc", "stc"),
+            &enable_component("html", "html"),
+            &enable_component("aui", "aui"),
+            "--disable-mediactrl",
+            &enable_component("propgrid", "propgrid"),
+            &enable_component("ribbon", "ribbon"),
+            &enable_component("richtext", "richtext"),
+            "--disable-webview",
+            &enable_component("xrc", "xrc"),
         ])
         .output()?;
+    assert!(
+        status.status.success(),
+        "{}",
+        std::str::from_utf8(&status.stderr)?
+    );
+
+    let jobs = env::var("NUM_JOBS").unwrap_or_else(|_| "1".to_string());
+    let status = Command::new("make")
+        .current_dir(&build_path)
+        .arg(format!("-j{}", jobs))
+        .output()?;
     let stdout = std::str::from_utf8(&status.stdout)?.to_string();
     let stderr = std::str::from_utf8(&status.stderr)?.to_string();
     println!("Building: {}", stdout);
     assert!(status.status.success(), "{}", stderr);
+    fs::write(built_stamp, "")?;
 
     Ok(())
 }
 
-fn get_libs(wx_path: &PathBuf) -> Result<String, Box<dyn Error>> {
+/// Configure-based builds happen out of the source tree, in a directory per cache key
+fn gtk_build_dir() -> PathBuf {
+    cache_dir().join("builds").join(cache_key())
+}
+
+/// The `wx-config` script produced by a configure-based build
+fn wx_config(wx_path: &Path) -> PathBuf {
+    if is_osx() {
+        wx_path.join("osx-release-build").join("wx-config")
+    } else {
+        gtk_build_dir().join("wx-config")
+    }
+}
+
+fn get_libs(wx_path: &Path) -> Result<String, Box<dyn Error>> {
     if is_msvc() {
         msvc_libs(wx_path)
     } else if is_windows() {
         windows_libs(wx_path)
-    } else if is_osx() {
-        osx_libs(wx_path)
+    } else if is_osx() || is_linux() {
+        Ok(format!(
+            "{} {}",
+            wx_config_libs(wx_path)?,
+            gtk_flags("--libs")?
+        ))
     } else {
         panic!("Unsupported platform")
     }
@@ -198,32 +680,36 @@ fn get_libs(wx_path: &PathBuf) -> Result<String, Box<dyn Error>> {
 
 fn msvc_libs(wx_path: &Path) -> Result<String, Box<dyn Error>> {
     Ok(format!(
-        "-L{} -lrpcrt4 -loleaut32 -lole32 -luuid -lwinspool -lwinmm -lshell32 -lcomctl32 -lcomdlg32 -ladvapi32 -lwsock32 -lgdi32 -loleacc -lversion -luxtheme -lshlwapi -luser32",
-        &wx_path.join("lib").join("vc_x64_lib").canonicalize()?.to_str().unwrap()[4..],
+        "-L{} -lrpcrt4 -loleaut32 -lole32 -luuid -lwinspool -lwinmm -lshell32 -lcomctl32 -lcomdlg32 -ladvapi32 -lwsock32 -lgdi32 -loleacc -lversion -luxtheme -lshlwapi -luser32 -limm32",
+        &msvc_lib_dir(wx_path).canonicalize()?.to_str().unwrap()[4..],
     ))
 }
 
 fn windows_libs(wx_path: &Path) -> Result<String, Box<dyn Error>> {
-    Ok(format!("-L{}/msw64-release-build/lib -lopengl32 -lwxtiff-3.1 -lwxjpeg-3.1 -lwxpng-3.1 -lwxregexu-3.1 -lwxscintilla-3.1 -lrpcrt4 -loleaut32 -lole32 -luuid -lwinspool -lwinmm -lshell32 -lcomctl32 -lcomdlg32 -ladvapi32 -lwsock32 -lgdi32 -lexpat -lz  -loleacc -lversion -luxtheme -lshlwapi -luser32", &wx_path.canonicalize()?.to_str().unwrap()[4..]))
+    Ok(format!("-L{}/msw64-release-build/lib -lopengl32 -lwxtiff-3.1 -lwxjpeg-3.1 -lwxpng-3.1 -lwxregexu-3.1 -lwxscintilla-3.1 -lrpcrt4 -loleaut32 -lole32 -luuid -lwinspool -lwinmm -lshell32 -lcomctl32 -lcomdlg32 -ladvapi32 -lwsock32 -lgdi32 -lexpat -lz  -loleacc -lversion -luxtheme -lshlwapi -luser32 -limm32", &wx_path.canonicalize()?.to_str().unwrap()[4..]))
 }
 
-fn osx_libs(wx_path: &Path) -> Result<String, Box<dyn Error>> {
-    Ok(String::from_utf8(
-        Command::new(format!("{}/osx-release-build/wx-config", wx_path.display()))
-            .arg("--libs")
-            .arg("core,base,gl")
-            .output()?
-            .stdout,
-    )?)
+fn wx_config_libs(wx_path: &Path) -> Result<String, Box<dyn Error>> {
+    wx_config_output(&wx_config(wx_path), &["--libs", &wx_config_lib_list()])
 }
 
-fn embed_resource_file(wx_path: &PathBuf) {
+fn wx_config_output(wx_config: &Path, args: &[&str]) -> Result<String, Box<dyn Error>> {
+    let output = Command::new(wx_config).args(args).output()?;
+    assert!(
+        output.status.success(),
+        "{}",
+        std::str::from_utf8(&output.stderr)?
+    );
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+fn embed_resource_file(wx_path: &Path) -> Result<(), Box<dyn Error>> {
     /*
     This was cribbed from https://github.com/nabijaczleweli/rust-embed-resource
     but extended to support the `--include-dir` argument to windres
     */
     let include_path = wx_path.join("include");
-    let resource_file = include_path.join("wx/msw/wx.rc");
+    let resource_file = resource_script(wx_path)?;
 
     let prefix = &resource_file
         .file_stem()
@@ -237,7 +723,7 @@ fn embed_resource_file(wx_path: &PathBuf) {
 
     // https://sourceware.org/binutils/docs/binutils/windres.html
     match Command::new("windres")
-        .args(&[
+        .args([
             "--include-dir",
             include_path.to_str().unwrap(),
             "--input",
@@ -260,4 +746,5 @@ fn embed_resource_file(wx_path: &PathBuf) {
     }
     println!("cargo:rustc-link-search=native={}", out_dir);
     println!("cargo:rustc-link-lib=dylib={}", prefix);
+    Ok(())
 }
@@ -1,6 +1,37 @@
 #include <wx/wx.h>
 #include "wx/clipbrd.h"
+#include "wx/iconbndl.h"
+#include "wx/mstream.h"
 #include <iostream>
+#include <map>
+#include <vector>
+
+// Optional components, enabled by the matching cargo feature. Each has an entry point at the
+// end of the file, which sets it up for the app's own native code
+#ifdef WXRS_USE_AUI
+  #include "wx/aui/aui.h"
+#endif
+#ifdef WXRS_USE_GL
+  #include "wx/glcanvas.h"
+#endif
+#ifdef WXRS_USE_HTML
+  #include "wx/html/htmlwin.h"
+#endif
+#ifdef WXRS_USE_PROPGRID
+  #include "wx/propgrid/propgrid.h"
+#endif
+#ifdef WXRS_USE_RIBBON
+  #include "wx/ribbon/bar.h"
+#endif
+#ifdef WXRS_USE_RICHTEXT
+  #include "wx/richtext/richtextctrl.h"
+#endif
+#ifdef WXRS_USE_STC
+  #include "wx/stc/stc.h"
+#endif
+#ifdef WXRS_USE_XRC
+  #include "wx/xrc/xmlres.h"
+#endif
 
 #if defined(_WIN32) || defined(_WIN64) || defined(WIN64) || defined(WIN32) || defined(__MINGW32__) || defined(__MINGW64__)
 #define __WINDOWS__
@@ -8,6 +39,22 @@
 
 #ifdef __WINDOWS__
   #include <windows.h>
+  #include <imm.h>
+#endif
+
+#ifdef __APPLE__
+  #include <objc/message.h>
+  #include <objc/runtime.h>
+#endif
+
+#ifdef __WXGTK__
+  #include <gtk/gtk.h>
+  #ifdef GDK_WINDOWING_X11
+    #include <gdk/gdkx.h>
+  #endif
+  #ifdef GDK_WINDOWING_WAYLAND
+    #include <gdk/gdkwayland.h>
+  #endif
 #endif
 
 #ifdef __WINDOWS__
@@ -68,29 +115,93 @@ wxStockCursor cursorToStock(cursor c) {
   else return wxCURSOR_ARROW;
 }
 
+// wxWidgets has no input method events, so the bridge defines its own
+wxDEFINE_EVENT(BRIDGE_EVT_COMPOSITION_START, wxCommandEvent);
+wxDEFINE_EVENT(BRIDGE_EVT_COMPOSITION_UPDATE, wxCommandEvent);
+wxDEFINE_EVENT(BRIDGE_EVT_COMPOSITION_COMMIT, wxCommandEvent);
+wxDEFINE_EVENT(BRIDGE_EVT_IME_TEXT, wxCommandEvent);
+wxDEFINE_EVENT(BRIDGE_EVT_RELATIVE_MOTION, wxCommandEvent);
+
+// The window that has keyboard focus, and so receives the input method's messages
+class CanvasWindow: public wxWindow {
+public:
+  CanvasWindow(wxWindow *parent)
+    : wxWindow(parent, wxID_ANY, wxDefaultPosition, wxSize(-1, -1),
+               wxBORDER_NONE | wxTRANSPARENT_WINDOW) {}
+
+#ifdef __WINDOWS__
+  WXLRESULT MSWWindowProc(WXUINT message, WXWPARAM wParam, WXLPARAM lParam) override;
+#endif
+};
+
 class MyFrame: public wxFrame {
 public:
-  MyFrame(const wxString& title, const wxSize& size);
+  MyFrame(int window_id, const wxString& title, long style);
   ~MyFrame();
 
+  // What the bridge calls this window
+  int window_id;
+
   wxCursor *cursors[NUMBER_OF_CURSORS];
+  cursor current_cursor;
   wxTimer *timer;
 
-  void (*render)();
-  void (*handle_events)(wxEvent &event);
-
   void OnPaint(wxPaintEvent &event);
   void OnEvent(wxEvent &event);
   void OnTimer(wxTimerEvent& event);
   void OnClose(wxCloseEvent &event);
+  void Render();
+  void HandleEvent(wxEvent &event);
 
   void BindEvents();
   void InitCursors();
 
-  wxWindow *inputWin;
+  // The cursor is hidden and kept in the middle of the canvas, and only deltas are sent
+  bool relative_mouse;
+  // Set by veto_close while the handler is looking at a vetoable close
+  bool close_vetoed;
+  void SetRelativeMouse(bool enabled);
+  void OnRelativeMotion(wxMouseEvent &event);
+
+  // Input method composition, delivered through handle_events
+  bool ime_allowed;
+  bool composing;
+  void SendImeEvent(wxEventType type, const wxString &text, int cursor);
+  void UpdateComposition(const wxString &text, int cursor);
+  void CommitComposition(const wxString &text);
+  void EndComposition();
+#ifdef __WXGTK__
+  GtkIMContext *ime_context;
+  // Set while a key is being filtered, when what it commits is held back in ime_plain_text
+  bool ime_filtering;
+  wxString ime_plain_text;
+  void InitIme();
+#endif
+
+  CanvasWindow *inputWin;
   wxDECLARE_EVENT_TABLE();
 };
 
+class MyApp: public wxApp {
+public:
+  MyApp();
+  // The window created by init_app
+  MyFrame *frame;
+  // Every open window, by ID
+  std::map<int, MyFrame *> frames;
+  int next_window_id;
+
+  // Shared by every window, which pass along their own ID
+  void (*render)(int window);
+  void (*handle_events)(int window, wxEvent &event);
+
+  MyFrame *CreateFrame(const wxString& title, long style);
+  MyFrame *FindFrame(int window);
+  virtual bool OnInit();
+};
+
+MyApp& wxGetApp() { return *static_cast<MyApp*>(wxApp::GetInstance()); }
+
 
 enum {
   TIMER_ID = 1,
@@ -102,110 +213,156 @@ EVT_CLOSE(MyFrame::OnClose)
 EVT_TIMER(TIMER_ID, MyFrame::OnTimer)
 wxEND_EVENT_TABLE()
 
-void MyFrame::OnTimer(wxTimerEvent& event)
-{
-  if (this->render) {
-    this->render();
+void MyFrame::Render() {
+  if (wxGetApp().render) {
+    wxGetApp().render(window_id);
   }
-  if (this->handle_events) {
-    this->handle_events(event);
+}
+
+void MyFrame::HandleEvent(wxEvent &event) {
+  if (wxGetApp().handle_events) {
+    wxGetApp().handle_events(window_id, event);
   }
 }
 
+void MyFrame::OnTimer(wxTimerEvent& event)
+{
+  Render();
+  HandleEvent(event);
+}
+
 void MyFrame::OnPaint( wxPaintEvent& WXUNUSED(event) ){
   wxPaintDC dc(this);
 
-  if (this->render) {
-    this->render();
-  }
+  Render();
 }
 
 
-MyFrame::MyFrame(const wxString& title, const wxSize& size)
-  : wxFrame(NULL, wxID_ANY, title, wxDefaultPosition, size)
+MyFrame::MyFrame(int id, const wxString& title, long style)
+  : wxFrame(NULL, wxID_ANY, title, wxDefaultPosition, wxDefaultSize, style), window_id(id)
 {
   // Needed to get App to catch key events on OSX
-  inputWin = new wxWindow(this, wxID_ANY,
-                          wxDefaultPosition,
-                          wxSize(-1, -1),
-                          wxBORDER_NONE | wxTRANSPARENT_WINDOW);
+  inputWin = new CanvasWindow(this);
+  inputWin->EnableTouchEvents(wxTOUCH_ALL_GESTURES);
+
+  ime_allowed = false;
+  composing = false;
+  relative_mouse = false;
+  close_vetoed = false;
+  current_cursor = ARROW_CURSOR;
 
   InitCursors();
-  Show(true);
+  BindEvents();
+#ifdef __WXGTK__
+  InitIme();
+#endif
 
   this->timer = new wxTimer(this, TIMER_ID);
   timer->Start(5);
 }
 
 MyFrame::~MyFrame() {
-  // Needed for graceful exit in Windows
-  wxTheApp->Unbind(wxEVT_MOTION, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_LEFT_DOWN, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_LEFT_UP, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_LEFT_DCLICK, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_MIDDLE_DOWN, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_MIDDLE_UP, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_MIDDLE_DCLICK, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_RIGHT_DOWN, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_RIGHT_UP, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_RIGHT_DCLICK, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_AUX1_DOWN, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_AUX1_UP, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_AUX1_DCLICK, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_AUX2_DOWN, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_AUX2_UP, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_AUX2_DCLICK, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_MOUSEWHEEL, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_KEY_DOWN, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_KEY_UP, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_SIZE, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_MOVE, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_ACTIVATE, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_LEAVE_WINDOW, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_ENTER_WINDOW, &MyFrame::OnEvent, this);
-  wxTheApp->Unbind(wxEVT_MENU, &MyFrame::OnEvent, this);
+  // The menu bar belongs to the Rust MenuBar, which deletes it, so wxFrame mustn't
+  SetMenuBar(NULL);
+#ifdef __WXGTK__
+  gtk_im_context_set_client_window(ime_context, NULL);
+  g_object_unref(ime_context);
+#endif
+  wxGetApp().frames.erase(window_id);
+  if (wxGetApp().frame == this) {
+    wxGetApp().frame = NULL;
+  }
 }
 
 void MyFrame::OnEvent(wxEvent &event){
-  if (this->handle_events) {
-    this->handle_events(event);
+  if (relative_mouse) {
+    if (event.GetEventType() == wxEVT_MOTION) {
+      OnRelativeMotion((wxMouseEvent &) event);
+      return;
+    } else if (event.GetEventType() == wxEVT_MOUSE_CAPTURE_LOST) {
+      SetRelativeMouse(false);
+    }
+  }
+  HandleEvent(event);
+  // Let wxWidgets carry on with the event, which lays out the canvas on resize and generates
+  // wxEVT_CHAR from key presses. A lost capture has to be marked as handled though
+  if (event.GetEventType() != wxEVT_MOUSE_CAPTURE_LOST) {
+    event.Skip();
   }
 }
 
 void MyFrame::BindEvents() {
-  wxTheApp->Bind(wxEVT_MOTION, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_LEFT_DOWN, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_LEFT_UP, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_LEFT_DCLICK, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_MIDDLE_DOWN, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_MIDDLE_UP, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_MIDDLE_DCLICK, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_RIGHT_DOWN, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_RIGHT_UP, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_RIGHT_DCLICK, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_AUX1_DOWN, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_AUX1_UP, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_AUX1_DCLICK, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_AUX2_DOWN, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_AUX2_UP, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_AUX2_DCLICK, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_MOUSEWHEEL, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_KEY_DOWN, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_KEY_UP, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_SIZE, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_MOVE, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_ACTIVATE, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_LEAVE_WINDOW, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_ENTER_WINDOW, &MyFrame::OnEvent, this);
-  wxTheApp->Bind(wxEVT_MENU, &MyFrame::OnEvent, this);
+  // Mouse, touch and keyboard input goes to the canvas, which fills the frame
+  inputWin->Bind(wxEVT_MOTION, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_LEFT_DOWN, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_LEFT_UP, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_LEFT_DCLICK, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_MIDDLE_DOWN, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_MIDDLE_UP, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_MIDDLE_DCLICK, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_RIGHT_DOWN, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_RIGHT_UP, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_RIGHT_DCLICK, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_AUX1_DOWN, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_AUX1_UP, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_AUX1_DCLICK, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_AUX2_DOWN, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_AUX2_UP, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_AUX2_DCLICK, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_MOUSEWHEEL, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_MAGNIFY, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_KEY_DOWN, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_KEY_UP, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_CHAR, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_LEAVE_WINDOW, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_ENTER_WINDOW, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_MOUSE_CAPTURE_LOST, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_GESTURE_PAN, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_GESTURE_ZOOM, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_GESTURE_ROTATE, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_TWO_FINGER_TAP, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_LONG_PRESS, &MyFrame::OnEvent, this);
+  inputWin->Bind(wxEVT_PRESS_AND_TAP, &MyFrame::OnEvent, this);
+  // Depending on the platform, keys can also go to the frame itself
+  Bind(wxEVT_KEY_DOWN, &MyFrame::OnEvent, this);
+  Bind(wxEVT_KEY_UP, &MyFrame::OnEvent, this);
+  Bind(wxEVT_CHAR, &MyFrame::OnEvent, this);
+  Bind(wxEVT_SIZE, &MyFrame::OnEvent, this);
+  Bind(wxEVT_MOVE, &MyFrame::OnEvent, this);
+  Bind(wxEVT_ACTIVATE, &MyFrame::OnEvent, this);
+  Bind(wxEVT_MENU, &MyFrame::OnEvent, this);
+  Bind(wxEVT_ICONIZE, &MyFrame::OnEvent, this);
+  Bind(wxEVT_MAXIMIZE, &MyFrame::OnEvent, this);
+  Bind(wxEVT_FULLSCREEN, &MyFrame::OnEvent, this);
 }
 
 void MyFrame::OnClose(wxCloseEvent& event)
 {
-  if (this->handle_events) {
-    this->handle_events(event);
+  if (event.CanVeto()) {
+    close_vetoed = false;
+    HandleEvent(event);
+    if (close_vetoed) {
+      event.Veto();
+      return;
+    }
+    // The close is going ahead, which is reported like one that couldn't be vetoed
+    wxCloseEvent closing(event);
+    closing.SetCanVeto(false);
+    HandleEvent(closing);
+  } else {
+    HandleEvent(event);
   }
   timer->Stop();
+  // The other windows close along with the main one, so that the app exits
+  if (wxGetApp().frame == this) {
+    std::vector<MyFrame *> others;
+    for (auto &f : wxGetApp().frames) {
+      if (f.second != this) others.push_back(f.second);
+    }
+    for (MyFrame *other : others) {
+      other->Close(true);
+    }
+  }
   event.Skip();
 }
 
@@ -215,6 +372,196 @@ void MyFrame::InitCursors() {
   }
 }
 
+void MyFrame::SetRelativeMouse(bool enabled) {
+  relative_mouse = enabled;
+  if (enabled) {
+    SetCursor(*cursors[NO_CURSOR]);
+    if (!inputWin->HasCapture()) {
+      inputWin->CaptureMouse();
+    }
+    wxSize size = inputWin->GetClientSize();
+    inputWin->WarpPointer(size.x / 2, size.y / 2);
+  } else {
+    if (inputWin->HasCapture()) {
+      inputWin->ReleaseMouse();
+    }
+    SetCursor(*cursors[current_cursor]);
+  }
+}
+
+void MyFrame::OnRelativeMotion(wxMouseEvent &event) {
+  wxSize size = inputWin->GetClientSize();
+  wxPoint center(size.x / 2, size.y / 2);
+  wxPoint delta = event.GetPosition() - center;
+  // Warping the pointer back to the center generates a motion event of its own
+  if (delta.x == 0 && delta.y == 0) return;
+  inputWin->WarpPointer(center.x, center.y);
+  wxCommandEvent relative(BRIDGE_EVT_RELATIVE_MOTION, GetId());
+  relative.SetEventObject(inputWin);
+  relative.SetInt(delta.x);
+  relative.SetExtraLong(delta.y);
+  HandleEvent(relative);
+}
+
+// `cursor` is a byte offset into the UTF-8 text, or -1 when the input method hides it
+void MyFrame::SendImeEvent(wxEventType type, const wxString &text, int cursor) {
+  wxCommandEvent event(type, GetId());
+  event.SetEventObject(inputWin);
+  event.SetString(text);
+  event.SetInt(cursor);
+  HandleEvent(event);
+}
+
+void MyFrame::UpdateComposition(const wxString &text, int cursor) {
+  if (!composing) {
+    // Some input methods clear the composition again after committing it
+    if (text.IsEmpty()) return;
+    composing = true;
+    SendImeEvent(BRIDGE_EVT_COMPOSITION_START, wxEmptyString, -1);
+  }
+  SendImeEvent(BRIDGE_EVT_COMPOSITION_UPDATE, text, cursor);
+}
+
+void MyFrame::CommitComposition(const wxString &text) {
+  if (composing) {
+    composing = false;
+    SendImeEvent(BRIDGE_EVT_COMPOSITION_COMMIT, text, -1);
+  } else {
+    // Input methods also commit text that was never composed, e.g. plain Latin keys
+    SendImeEvent(BRIDGE_EVT_IME_TEXT, text, -1);
+  }
+}
+
+// A composition that ends without being committed was cancelled
+void MyFrame::EndComposition() {
+  if (composing) {
+    composing = false;
+    SendImeEvent(BRIDGE_EVT_COMPOSITION_COMMIT, wxEmptyString, -1);
+  }
+}
+
+#ifdef __WINDOWS__
+wxString getCompositionString(HIMC himc, DWORD index) {
+  LONG len = ImmGetCompositionStringW(himc, index, NULL, 0);
+  if (len <= 0) return wxEmptyString;
+  wxWCharBuffer buf(len / sizeof(wchar_t));
+  ImmGetCompositionStringW(himc, index, buf.data(), len);
+  return wxString(buf.data(), len / sizeof(wchar_t));
+}
+
+WXLRESULT CanvasWindow::MSWWindowProc(WXUINT message, WXWPARAM wParam, WXLPARAM lParam) {
+  MyFrame *frame = (MyFrame *) GetParent();
+  if (frame->ime_allowed) {
+    switch (message) {
+      case WM_IME_SETCONTEXT:
+        // The app draws the composition itself
+        lParam &= ~ISC_SHOWUICOMPOSITIONWINDOW;
+        break;
+      case WM_IME_STARTCOMPOSITION:
+        return 0;
+      case WM_IME_COMPOSITION: {
+        HIMC himc = ImmGetContext(GetHWND());
+        if (himc) {
+          if (lParam & GCS_RESULTSTR) {
+            frame->CommitComposition(getCompositionString(himc, GCS_RESULTSTR));
+          }
+          if (lParam & GCS_COMPSTR) {
+            wxString text = getCompositionString(himc, GCS_COMPSTR);
+            int cursor = -1;
+            if (lParam & GCS_CURSORPOS) {
+              // The position is in UTF-16 units
+              LONG pos = ImmGetCompositionStringW(himc, GCS_CURSORPOS, NULL, 0);
+              cursor = strlen(text.Left(pos).utf8_str());
+            }
+            frame->UpdateComposition(text, cursor);
+          }
+          ImmReleaseContext(GetHWND(), himc);
+        }
+        return 0;
+      }
+      case WM_IME_ENDCOMPOSITION:
+        frame->EndComposition();
+        return 0;
+    }
+  }
+  return wxWindow::MSWWindowProc(message, wParam, lParam);
+}
+#endif
+
+#ifdef __WXGTK__
+// Send text held back while filtering a key, before anything that comes after it
+static void imeFlushPlainText(MyFrame *frame) {
+  if (!frame->ime_plain_text.IsEmpty()) {
+    frame->CommitComposition(frame->ime_plain_text);
+    frame->ime_plain_text.Clear();
+  }
+}
+
+static void imePreeditChanged(GtkIMContext *context, MyFrame *frame) {
+  imeFlushPlainText(frame);
+  gchar *str;
+  gint cursor;
+  gtk_im_context_get_preedit_string(context, &str, NULL, &cursor);
+  // The cursor is in characters
+  int offset = g_utf8_offset_to_pointer(str, cursor) - str;
+  frame->UpdateComposition(wxString::FromUTF8(str), offset);
+  g_free(str);
+}
+
+static void imePreeditEnd(GtkIMContext *, MyFrame *frame) {
+  frame->EndComposition();
+}
+
+static void imeCommit(GtkIMContext *, gchar *str, MyFrame *frame) {
+  // Outside a composition, the key might just be typing itself, which wxWidgets reports
+  if (frame->ime_filtering && !frame->composing) {
+    frame->ime_plain_text += wxString::FromUTF8(str);
+    return;
+  }
+  frame->CommitComposition(wxString::FromUTF8(str));
+}
+
+// Connected to the generic "event" signal of the top level window, which is emitted before
+// wxWidgets handles the key itself. Keys are only kept from wxWidgets while they're part of
+// a composition, or when the input method turns them into other text, so that the handler
+// still gets KeyDown and KeyUp for the rest
+static gboolean imeFilterKey(GtkWidget *, GdkEvent *event, MyFrame *frame) {
+  if (event->type != GDK_KEY_PRESS && event->type != GDK_KEY_RELEASE) return FALSE;
+  if (!frame->ime_allowed || wxWindow::FindFocus() != frame->inputWin) return FALSE;
+  GdkEventKey *key = (GdkEventKey *) event;
+  bool was_composing = frame->composing;
+  frame->ime_filtering = true;
+  gboolean filtered = gtk_im_context_filter_keypress(frame->ime_context, key);
+  frame->ime_filtering = false;
+  wxString text = frame->ime_plain_text;
+  frame->ime_plain_text.Clear();
+  if (!filtered) return FALSE;
+  if (was_composing || frame->composing) {
+    if (!text.IsEmpty()) frame->CommitComposition(text);
+    return TRUE;
+  }
+  if (event->type == GDK_KEY_RELEASE) return FALSE;
+  // Taken by the input method without any text, e.g. to switch it on
+  if (text.IsEmpty()) return TRUE;
+  gunichar c = gdk_keyval_to_unicode(key->keyval);
+  if (c != 0 && text == wxString(wxUniChar(c))) {
+    // wxWidgets sends the key and its character itself
+    return FALSE;
+  }
+  frame->CommitComposition(text);
+  return TRUE;
+}
+
+void MyFrame::InitIme() {
+  ime_filtering = false;
+  ime_context = gtk_im_multicontext_new();
+  g_signal_connect(ime_context, "preedit-changed", G_CALLBACK(imePreeditChanged), this);
+  g_signal_connect(ime_context, "preedit-end", G_CALLBACK(imePreeditEnd), this);
+  g_signal_connect(ime_context, "commit", G_CALLBACK(imeCommit), this);
+  g_signal_connect(GetHandle(), "event", G_CALLBACK(imeFilterKey), this);
+}
+#endif
+
 enum bridge_event_type {
   UNKNOWN_EVENT,
   MOUSE_MOTION,
@@ -244,6 +591,24 @@ enum bridge_event_type {
   TIMER,
   EXIT,
   MENU,
+  TEXT_INPUT,
+  COMPOSITION_START,
+  COMPOSITION_UPDATE,
+  COMPOSITION_COMMIT,
+  IME_TEXT_INPUT,
+  MOUSE_CAPTURE_LOST,
+  RELATIVE_MOTION,
+  GESTURE_PAN,
+  GESTURE_ZOOM,
+  GESTURE_ROTATE,
+  TWO_FINGER_TAP,
+  LONG_PRESS,
+  PRESS_AND_TAP,
+  MAGNIFY,
+  ICONIZE,
+  MAXIMIZE,
+  FULLSCREEN,
+  CLOSE_REQUESTED,
 };
 
 int wxEventTypeToBridgeEventType(wxEventType t) {
@@ -274,28 +639,47 @@ int wxEventTypeToBridgeEventType(wxEventType t) {
   else if (t == wxEVT_TIMER) return TIMER;
   else if (t == wxEVT_CLOSE_WINDOW) return EXIT;
   else if (t == wxEVT_MENU) return MENU;
+  else if (t == wxEVT_CHAR) return TEXT_INPUT;
+  else if (t == BRIDGE_EVT_COMPOSITION_START) return COMPOSITION_START;
+  else if (t == BRIDGE_EVT_COMPOSITION_UPDATE) return COMPOSITION_UPDATE;
+  else if (t == BRIDGE_EVT_COMPOSITION_COMMIT) return COMPOSITION_COMMIT;
+  else if (t == BRIDGE_EVT_IME_TEXT) return IME_TEXT_INPUT;
+  else if (t == wxEVT_MOUSE_CAPTURE_LOST) return MOUSE_CAPTURE_LOST;
+  else if (t == BRIDGE_EVT_RELATIVE_MOTION) return RELATIVE_MOTION;
+  else if (t == wxEVT_GESTURE_PAN) return GESTURE_PAN;
+  else if (t == wxEVT_GESTURE_ZOOM) return GESTURE_ZOOM;
+  else if (t == wxEVT_GESTURE_ROTATE) return GESTURE_ROTATE;
+  else if (t == wxEVT_TWO_FINGER_TAP) return TWO_FINGER_TAP;
+  else if (t == wxEVT_LONG_PRESS) return LONG_PRESS;
+  else if (t == wxEVT_PRESS_AND_TAP) return PRESS_AND_TAP;
+  else if (t == wxEVT_MAGNIFY) return MAGNIFY;
+  else if (t == wxEVT_ICONIZE) return ICONIZE;
+  else if (t == wxEVT_MAXIMIZE) return MAXIMIZE;
+  else if (t == wxEVT_FULLSCREEN) return FULLSCREEN;
   else return UNKNOWN_EVENT;
 }
 
-class MyApp: public wxApp {
-public:
-  MyApp(const wxString& title, const wxSize& size);
-  MyFrame *frame;
-  virtual bool OnInit();
-private:
-  wxString title;
-  wxSize size;
-};
-
+// The main window is created by init_app, once it knows how
 bool MyApp::OnInit() {
-  frame = new MyFrame( title, size );
-  frame->Show( true );
+  // For reading icons
+  wxInitAllImageHandlers();
   return true;
 }
 
-MyApp::MyApp(const wxString& t, const wxSize& s) : title(t), size(s) {}
+// Not shown until it has been sized and placed
+MyFrame *MyApp::CreateFrame(const wxString& title, long style) {
+  MyFrame *f = new MyFrame(next_window_id++, title, style);
+  frames[f->window_id] = f;
+  return f;
+}
 
-MyApp& wxGetApp() { return *static_cast<MyApp*>(wxApp::GetInstance()); }
+MyFrame *MyApp::FindFrame(int window) {
+  auto f = frames.find(window);
+  return f == frames.end() ? NULL : f->second;
+}
+
+MyApp::MyApp()
+  : frame(NULL), next_window_id(1), render(NULL), handle_events(NULL) {}
 
 class CustomDataObject : public wxDataObjectSimple
 {
@@ -334,30 +718,200 @@ extern "C" {
     int y;
   };
 
-  void init_app(char* name, unsigned int width, unsigned int height) {
+  // The size is of the client area, like the size limits, in logical pixels. FromDIP scales
+  // it by the display's scale factor on Windows, and leaves it alone elsewhere
+  void sizeFrame(MyFrame *frame, unsigned int width, unsigned int height) {
+    frame->SetClientSize(frame->FromDIP(wxSize(width, height)));
+#ifdef __APPLE__
+    // Keep the title bar clear of the menu bar
+    if (frame->GetPosition().y < 20.0) {
+      frame->Move(0.0, 20.0);
+    }
+#endif
+  }
+
+  struct WindowOptions {
+    char *title;
+    // 0 for the platform's default
+    unsigned int width;
+    unsigned int height;
+    bool has_position;
+    int x;
+    int y;
+    // -1 for no limit
+    int min_width;
+    int min_height;
+    int max_width;
+    int max_height;
+    bool resizable;
+    bool borderless;
+    bool always_on_top;
+    bool tool_window;
+    bool visible;
+  };
+
+  long windowStyle(const WindowOptions &options) {
+    long style = wxDEFAULT_FRAME_STYLE;
+    if (options.borderless) {
+      style = wxBORDER_NONE | wxCLIP_CHILDREN;
+    } else if (!options.resizable) {
+      style &= ~(wxRESIZE_BORDER | wxMAXIMIZE_BOX);
+    }
+    if (options.always_on_top) {
+      style |= wxSTAY_ON_TOP;
+    }
+    if (options.tool_window) {
+      style |= wxFRAME_TOOL_WINDOW;
+    }
+    return style;
+  }
+
+  // Size limits are given for the client area in logical pixels, but wxWidgets wants them for
+  // the whole frame
+  wxSize frameSizeLimit(MyFrame *frame, int width, int height) {
+    if (width < 0 || height < 0) {
+      return wxDefaultSize;
+    }
+    return frame->ClientToWindowSize(frame->FromDIP(wxSize(width, height)));
+  }
+
+  MyFrame *createFrame(const WindowOptions &options) {
+    MyFrame *frame = wxGetApp().CreateFrame(wxString::FromUTF8(options.title), windowStyle(options));
+    if (options.width > 0 && options.height > 0) {
+      sizeFrame(frame, options.width, options.height);
+    }
+    frame->SetSizeHints(
+      frameSizeLimit(frame, options.min_width, options.min_height),
+      frameSizeLimit(frame, options.max_width, options.max_height)
+    );
+    if (options.has_position) {
+      frame->Move(options.x, options.y);
+    }
+#if defined(__WINDOWS__) && defined(WXRS_ICON)
+    // The icon the build script embedded, named so that it's also the executable's icon
+    frame->SetIcons(wxIconBundle("aaaa", (WXHINSTANCE) NULL));
+#endif
+    frame->Show(options.visible);
+    return frame;
+  }
+
+  void init_app(WindowOptions options) {
     setDPIAware();
-    wxApp::SetInstance(new MyApp(name, wxSize(width, height)));
+    wxApp::SetInstance(new MyApp());
     int fake_argc = 0;
     char *fake_argv[1] = {NULL};
     wxEntryStart(fake_argc, fake_argv);
     wxTheApp->OnInit();
 
-#ifdef __WINDOWS__
-    // Adjust the window according to the display scale factor
-    double scale = wxGetApp().frame->GetDPIScaleFactor();
-    wxGetApp().frame->SetSize(width * scale, height * scale);
-#else
-    // OSX
-    double scale = wxGetApp().frame->GetDPIScaleFactor();
-    wxGetApp().frame->SetSize(width, height);
-    if (wxGetApp().frame->GetPosition().y < 20.0) {
-      wxGetApp().frame->Move(0.0, 20.0);
+    wxGetApp().frame = createFrame(options);
+  }
+
+  int get_main_window() {
+    MyFrame *frame = wxGetApp().frame;
+    return frame ? frame->window_id : 0;
+  }
+
+  int create_window(WindowOptions options) {
+    return createFrame(options)->window_id;
+  }
+
+  void set_title(int window, char *title) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->SetTitle(wxString::FromUTF8(title));
     }
-#endif
   }
 
-  void set_render(void (*render)()) {
-    wxGetApp().frame->render = render;
+  void set_size(int window, unsigned int width, unsigned int height) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      sizeFrame(frame, width, height);
+    }
+  }
+
+  // In the same units as wxEVT_MOVE reports
+  void set_position(int window, Size position) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->Move(position.x, position.y);
+    }
+  }
+
+  void set_visible(int window, bool visible) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->Show(visible);
+    }
+  }
+
+  void set_fullscreen(int window, bool fullscreen) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->ShowFullScreen(fullscreen, wxFULLSCREEN_ALL);
+    }
+  }
+
+  void maximize(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->Maximize(true);
+    }
+  }
+
+  void minimize(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->Iconize(true);
+    }
+  }
+
+  // Back from being maximized or minimized
+  void restore(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->Restore();
+    }
+  }
+
+  bool is_fullscreen(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    return frame && frame->IsFullScreen();
+  }
+
+  bool is_maximized(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    return frame && frame->IsMaximized();
+  }
+
+  bool is_minimized(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    return frame && frame->IsIconized();
+  }
+
+  void close_window(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->Close(true);
+    }
+  }
+
+  void veto_close(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->close_vetoed = true;
+    }
+  }
+
+  // Close like the close button does, sending a CloseRequested that can be vetoed
+  void request_close(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->Close(false);
+    }
+  }
+
+  void set_render(void (*render)(int window)) {
+    wxGetApp().render = render;
   }
 
   void run_app() {
@@ -372,16 +926,22 @@ extern "C" {
     void *ns_view;
   };
 
-  OSXHandle get_osx_raw_window_handle() {
+  OSXHandle get_osx_raw_window_handle(int window) {
     struct OSXHandle h = {NULL, NULL};
-    h.ns_view = (void *) wxGetApp().frame->GetHandle();
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      h.ns_view = (void *) frame->GetHandle();
+    }
     return h;
   }
 
 #endif
 
   void close_app() {
-    wxGetApp().frame->Close(true);
+    // Closing the main window closes the rest
+    if (wxGetApp().frame) {
+      wxGetApp().frame->Close(true);
+    }
   }
 
 #ifdef __WINDOWS__
@@ -390,63 +950,130 @@ extern "C" {
     void *hinstance;
   };
 
-  WindowsHandle get_windows_raw_window_handle() {
+  WindowsHandle get_windows_raw_window_handle(int window) {
     struct WindowsHandle h = {NULL, NULL};
-    HWND hwnd = (HWND) wxGetApp().frame->GetHandle();
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (!frame) {
+      return h;
+    }
+    HWND hwnd = (HWND) frame->GetHandle();
     h.hwnd = (void *) hwnd;
     h.hinstance = (void *) GetWindowLongPtr(hwnd, GWLP_HINSTANCE);
     return h;
   }
 #endif
 
+#ifdef __WXGTK__
+  struct X11Handle {
+    unsigned long window;
+    void *display;
+    int screen;
+  };
+
+  // Wayland handles aren't given out, since GTK draws the decorations, menu bar and status bar
+  // into the same surface as the canvas
+  bool is_wayland() {
+#ifdef GDK_WINDOWING_WAYLAND
+    return GDK_IS_WAYLAND_DISPLAY(gdk_display_get_default());
+#else
+    return false;
+#endif
+  }
+
+  X11Handle get_x11_raw_window_handle(int window_id) {
+    struct X11Handle h = {0, NULL, 0};
+#ifdef GDK_WINDOWING_X11
+    MyFrame *frame = wxGetApp().FindFrame(window_id);
+    GdkWindow *window = frame ? frame->GTKGetDrawingWindow() : NULL;
+    if (window && GDK_IS_X11_WINDOW(window)) {
+      h.window = gdk_x11_window_get_xid(window);
+      h.display = (void *) gdk_x11_display_get_xdisplay(gdk_window_get_display(window));
+      h.screen = gdk_x11_screen_get_screen_number(gdk_window_get_screen(window));
+    }
+#endif
+    return h;
+  }
+#endif
+
   // How many pixels in the canvas?
-  Size get_display_size() {
+  Size get_display_size(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (!frame) {
+      return Size {0, 0};
+    }
 #ifdef __APPLE__
-    wxSize s =  wxGetApp().frame->GetClientSize();
+    wxSize s =  frame->GetClientSize();
     struct Size sz = {s.x, s.y};
     return sz;
+#elif defined(__WXGTK__)
+    wxSize s = frame->GetClientSize();
+    double scale_factor = frame->GetContentScaleFactor();
+    return Size {(int)(s.x * scale_factor), (int)(s.y * scale_factor)};
 #else
-    struct wxSize s = wxGetApp().frame->GetClientSize();
+    struct wxSize s = frame->GetClientSize();
     return Size { s.x, s.y };
 #endif
   }
 
   // The logical window size
-  Size get_client_size() {
+  Size get_client_size(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (!frame) {
+      return Size {0, 0};
+    }
 #ifdef __APPLE__
     int status_bar_height;
-    if (wxGetApp().frame->GetStatusBar()) {
-      status_bar_height = wxGetApp().frame->GetStatusBar()->GetRect().height;
+    if (frame->GetStatusBar()) {
+      status_bar_height = frame->GetStatusBar()->GetRect().height;
     } else {
       status_bar_height = 0;
     }
-    float scale_factor = wxGetApp().frame->GetDPIScaleFactor();
-    wxSize s =  wxGetApp().frame->GetClientSize();
+    float scale_factor = frame->GetDPIScaleFactor();
+    wxSize s =  frame->GetClientSize();
     struct Size sz = {s.x * scale_factor, (s.y + status_bar_height) * scale_factor};
     return sz;
-#else
-    HWND hwnd = (HWND) wxGetApp().frame->GetHandle();
+#elif defined(__WINDOWS__)
+    HWND hwnd = (HWND) frame->GetHandle();
     RECT rect;
     GetClientRect(hwnd, &rect);
-    float scale_factor = wxGetApp().frame->GetDPIScaleFactor();
+    float scale_factor = frame->GetDPIScaleFactor();
     return Size {(int)((rect.right - rect.left) / scale_factor), (int)((rect.bottom - rect.top) / scale_factor)};
+#else
+    // GTK reports client sizes in logical pixels
+    wxSize s = frame->GetClientSize();
+    return Size { s.x, s.y };
 #endif
   }
 
-  float get_scale_factor() {
-    return wxGetApp().frame->GetDPIScaleFactor();
+  float get_scale_factor(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (!frame) {
+      return 1.0;
+    }
+#ifdef __WXGTK__
+    // GTK only scales by integer factors, which GetDPIScaleFactor doesn't reflect
+    return frame->GetContentScaleFactor();
+#else
+    return frame->GetDPIScaleFactor();
+#endif
   }
 
-  void refresh() {
-    return wxGetApp().frame->Refresh();
+  void refresh(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->Refresh();
+    }
   }
 
-  void bind_canvas_events(void (*handle_events)(wxEvent &event)) {
-    wxGetApp().frame->handle_events = handle_events;
-    wxGetApp().frame->BindEvents();
+  void bind_canvas_events(void (*handle_events)(int window, wxEvent &event)) {
+    wxGetApp().handle_events = handle_events;
   }
 
   int get_event_type(wxEvent &event) {
+    // Only closes that can be vetoed are requests
+    if (event.GetEventType() == wxEVT_CLOSE_WINDOW && ((wxCloseEvent &) event).CanVeto()) {
+      return CLOSE_REQUESTED;
+    }
     return wxEventTypeToBridgeEventType(event.GetEventType());
   }
 
@@ -459,6 +1086,29 @@ extern "C" {
     return event.GetKeyCode();
   }
 
+  unsigned int get_raw_key_code(wxKeyEvent &event) {
+    return event.GetRawKeyCode();
+  }
+
+  // The physical key: a scan code with 0xE000 set for extended keys on Windows, an evdev code
+  // on GTK and a virtual key code on macOS. See `ScanCode::from_native`
+  unsigned int get_native_scan_code(wxKeyEvent &event) {
+#ifdef __WINDOWS__
+    unsigned int flags = event.GetRawKeyFlags();
+    unsigned int scan_code = (flags >> 16) & 0xFF;
+    return (flags & (1 << 24)) ? (0xE000 | scan_code) : scan_code;
+#elif defined(__WXGTK__)
+    // X11 keycodes are evdev codes offset by 8, and Wayland uses the same numbering
+    return event.GetRawKeyFlags() - 8;
+#else
+    return event.GetRawKeyCode();
+#endif
+  }
+
+  bool is_auto_repeat(wxKeyEvent &event) {
+    return event.IsAutoRepeat();
+  }
+
   wxChar get_event_char(wxKeyEvent &event) {
     return event.GetUnicodeKey();
   }
@@ -467,27 +1117,128 @@ extern "C" {
     return event.GetModifiers();
   }
 
-  bool shift_down(wxKeyEvent &event) {
-    return event.ShiftDown();
+  int get_mouse_modifiers(wxMouseEvent &event) {
+    return event.GetModifiers();
+  }
+
+  enum lock_modifiers {
+    CAPS_LOCK = 1 << 8,
+    NUM_LOCK = 1 << 9,
+    SCROLL_LOCK = 1 << 10,
+  };
+
+  int get_keyboard_state() {
+    int modifiers = wxGetMouseState().GetModifiers();
+    // For lock keys, wxGetKeyState reports whether they're toggled on
+    if (wxGetKeyState(WXK_CAPITAL)) modifiers |= CAPS_LOCK;
+    if (wxGetKeyState(WXK_NUMLOCK)) modifiers |= NUM_LOCK;
+    if (wxGetKeyState(WXK_SCROLL)) modifiers |= SCROLL_LOCK;
+    return modifiers;
+  }
+
+  Size get_move_position(wxMoveEvent &event) {
+    wxPoint p = event.GetPosition();
+    return Size { p.x, p.y };
+  }
+
+  int get_ime_text_len(wxCommandEvent &event) {
+    return strlen(event.GetString().utf8_str());
+  }
+
+  void get_ime_text(wxCommandEvent &event, char *str) {
+    wxScopedCharBuffer src = event.GetString().utf8_str();
+    memcpy(str, src.data(), src.length());
+  }
+
+  int get_ime_cursor(wxCommandEvent &event) {
+    return event.GetInt();
   }
 
   bool get_event_focused(wxActivateEvent &event) {
     return event.GetActive();
   }
 
-  Size get_mouse_position(wxMouseEvent &event) {
-#ifdef __APPLE__
-    wxPoint p = event.GetPosition();
-    struct Size s = {p.x, p.y };
-    return s;
+  bool get_event_iconized(wxIconizeEvent &event) {
+    return event.IsIconized();
+  }
+
+  bool get_event_fullscreen(wxFullScreenEvent &event) {
+    return event.IsFullScreen();
+  }
+
+  // Mouse positions are in logical pixels on every platform, scaled by the window they're in
+  Size toLogicalPosition(wxWindow *win, wxPoint p) {
+#if defined(__APPLE__) || defined(__WXGTK__)
+    return Size { p.x, p.y };
 #else
     // TODO Doesn't seem to take into account status bar height
-    wxPoint p = event.GetPosition();
-    float scale_factor = wxGetApp().frame->GetDPIScaleFactor();
+    float scale_factor = win ? win->GetDPIScaleFactor() : 1.0;
     return Size {(int)(p.x / scale_factor), (int)(p.y / scale_factor)};
 #endif
   }
 
+  Size fromLogicalPosition(wxWindow *win, Size p) {
+#if defined(__APPLE__) || defined(__WXGTK__)
+    return p;
+#else
+    float scale_factor = win ? win->GetDPIScaleFactor() : 1.0;
+    return Size {(int)(p.x * scale_factor), (int)(p.y * scale_factor)};
+#endif
+  }
+
+  wxWindow *eventWindow(wxEvent &event) {
+    return wxDynamicCast(event.GetEventObject(), wxWindow);
+  }
+
+  Size get_mouse_position(wxMouseEvent &event) {
+    return toLogicalPosition(eventWindow(event), event.GetPosition());
+  }
+
+  Size get_pointer_position(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (!frame) {
+      return Size {0, 0};
+    }
+    return toLogicalPosition(frame, frame->inputWin->ScreenToClient(wxGetMousePosition()));
+  }
+
+  void warp_pointer(int window, Size p) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      Size position = fromLogicalPosition(frame, p);
+      frame->inputWin->WarpPointer(position.x, position.y);
+    }
+  }
+
+  // Unscaled, as the platform reports mouse positions
+  Size get_relative_motion(wxCommandEvent &event) {
+    return Size { event.GetInt(), (int) event.GetExtraLong() };
+  }
+
+  Size get_gesture_position(wxGestureEvent &event) {
+    return toLogicalPosition(eventWindow(event), event.GetPosition());
+  }
+
+  bool is_gesture_start(wxGestureEvent &event) {
+    return event.IsGestureStart();
+  }
+
+  bool is_gesture_end(wxGestureEvent &event) {
+    return event.IsGestureEnd();
+  }
+
+  Size get_pan_delta(wxPanGestureEvent &event) {
+    return toLogicalPosition(eventWindow(event), event.GetDelta());
+  }
+
+  float get_zoom_factor(wxZoomGestureEvent &event) {
+    return event.GetZoomFactor();
+  }
+
+  float get_rotation_angle(wxRotateGestureEvent &event) {
+    return event.GetRotationAngle();
+  }
+
   int get_mouse_wheel_rotation(wxMouseEvent &event) {
     return event.GetWheelRotation();
   }
@@ -500,18 +1251,137 @@ extern "C" {
     return event.GetWheelDelta();
   }
 
-  void set_cursor(cursor c) {
-    MyFrame *frame = wxGetApp().frame;
-    frame->SetCursor(*frame->cursors[c]);
+  int get_mouse_wheel_lines_per_action(wxMouseEvent &event) {
+    return event.GetLinesPerAction();
+  }
+
+  int get_mouse_wheel_columns_per_action(wxMouseEvent &event) {
+    return event.GetColumnsPerAction();
+  }
+
+  bool is_mouse_wheel_inverted(wxMouseEvent &event) {
+    return event.IsWheelInverted();
+  }
+
+  // Whether the rotation is in pixels, as trackpads scroll, rather than in notches of a wheel
+  bool is_mouse_wheel_precise(wxMouseEvent &event) {
+#ifdef __APPLE__
+    // wxWidgets doesn't keep this, but the NSEvent being handled does
+    id ns_event = (id) wxTheApp->MacGetCurrentEvent();
+    if (ns_event) {
+      SEL precise = sel_registerName("hasPreciseScrollingDeltas");
+      return ((BOOL (*)(id, SEL)) objc_msgSend)(ns_event, precise);
+    }
+#endif
+    // Otherwise only tell by a rotation that isn't a whole number of notches
+    return event.GetWheelDelta() != 0 && event.GetWheelRotation() % event.GetWheelDelta() != 0;
+  }
+
+  float get_magnification(wxMouseEvent &event) {
+    return event.GetMagnification();
+  }
+
+  void set_cursor(int window, cursor c) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (!frame) {
+      return;
+    }
+    frame->current_cursor = c;
+    // The cursor stays hidden until relative mouse mode is turned off
+    if (!frame->relative_mouse) {
+      frame->SetCursor(*frame->cursors[c]);
+    }
+  }
+
+  void capture_mouse(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame && !frame->inputWin->HasCapture()) {
+      frame->inputWin->CaptureMouse();
+    }
+  }
+
+  void release_mouse(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame && frame->inputWin->HasCapture()) {
+      frame->inputWin->ReleaseMouse();
+    }
+  }
+
+  void set_relative_mouse_mode(int window, bool enabled) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame && frame->relative_mouse != enabled) {
+      frame->SetRelativeMouse(enabled);
+    }
+  }
+
+  struct Rect {
+    int x;
+    int y;
+    unsigned int width;
+    unsigned int height;
+  };
+
+  // Where the input method should show its candidates, in logical pixels of the canvas
+  void set_ime_cursor_area(int window, Rect r) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (!frame) {
+      return;
+    }
+#ifdef __WINDOWS__
+    HWND hwnd = (HWND) frame->inputWin->GetHWND();
+    double scale = frame->GetDPIScaleFactor();
+    RECT area = {
+      (LONG) (r.x * scale), (LONG) (r.y * scale),
+      (LONG) ((r.x + r.width) * scale), (LONG) ((r.y + r.height) * scale)
+    };
+    HIMC himc = ImmGetContext(hwnd);
+    if (himc) {
+      COMPOSITIONFORM composition = {CFS_POINT, {area.left, area.top}, {0, 0, 0, 0}};
+      ImmSetCompositionWindow(himc, &composition);
+      CANDIDATEFORM candidate = {0, CFS_EXCLUDE, {area.left, area.top}, area};
+      ImmSetCandidateWindow(himc, &candidate);
+      ImmReleaseContext(hwnd, himc);
+    }
+#elif defined(__WXGTK__)
+    GdkRectangle area = {r.x, r.y, (int) r.width, (int) r.height};
+    gtk_im_context_set_cursor_location(frame->ime_context, &area);
+#endif
+  }
+
+  void set_ime_allowed(int window, bool allowed) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (!frame) {
+      return;
+    }
+    if (!allowed) {
+      frame->EndComposition();
+    }
+    frame->ime_allowed = allowed;
+#ifdef __WINDOWS__
+    // Without an input context, keys go straight to the window
+    ImmAssociateContextEx((HWND) frame->inputWin->GetHWND(), NULL, allowed ? IACE_DEFAULT : 0);
+#elif defined(__WXGTK__)
+    if (allowed) {
+      gtk_im_context_set_client_window(frame->ime_context, frame->inputWin->GTKGetDrawingWindow());
+      gtk_im_context_focus_in(frame->ime_context);
+    } else {
+      gtk_im_context_reset(frame->ime_context);
+      gtk_im_context_focus_out(frame->ime_context);
+    }
+#endif
   }
 
-  void create_status_bar() {
-    wxGetApp().frame->CreateStatusBar();
+  void create_status_bar(int window) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->CreateStatusBar();
+    }
   }
 
-  void set_status_text(char *text) {
-    if (wxGetApp().frame->GetStatusBar()) {
-      wxGetApp().frame->SetStatusText(text);
+  void set_status_text(int window, char *text) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame && frame->GetStatusBar()) {
+      frame->SetStatusText(text);
     }
   }
 
@@ -619,8 +1489,11 @@ extern "C" {
     }
   }
 
-  void set_status_menu(wxMenu *menu) {
-    wxGetApp().frame->PopupMenu(menu);
+  void set_status_menu(int window, wxMenu *menu) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->PopupMenu(menu);
+    }
   }
 
   void delete_menu(wxMenu *menu) {
@@ -643,14 +1516,142 @@ extern "C" {
     }
   }
 
-  void set_menu_bar(wxMenuBar *menuBar) {
-    wxGetApp().frame->SetMenuBar(menuBar);
+  void set_menu_bar(int window, wxMenuBar *menuBar) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (!frame) {
+      return;
+    }
+    // A menu bar can only belong to one window at a time
+    wxFrame *previous = menuBar->GetFrame();
+    if (previous && previous != frame) {
+      previous->SetMenuBar(NULL);
+    }
+    frame->SetMenuBar(menuBar);
   }
 
   void delete_menu_bar(wxMenuBar *menuBar) {
-    if (wxGetApp().frame->GetMenuBar() == menuBar) {
-      wxGetApp().frame->SetMenuBar(NULL);
+    // The app is gone once run_app has returned, and its frames with it
+    if (wxApp::GetInstance()) {
+      for (auto &f : wxGetApp().frames) {
+        if (f.second->GetMenuBar() == menuBar) {
+          f.second->SetMenuBar(NULL);
+        }
+      }
     }
     delete menuBar;
   }
+
+  // Icons
+  void *create_icon_bundle() {
+    return (void *) new wxIconBundle;
+  }
+
+  void add_rgba_to_icon_bundle(wxIconBundle *bundle, unsigned int width, unsigned int height, unsigned char *rgba) {
+    wxImage image(width, height, false);
+    image.InitAlpha();
+    unsigned char *rgb = image.GetData();
+    unsigned char *alpha = image.GetAlpha();
+    for (size_t i = 0; i < (size_t) width * height; i++) {
+      rgb[i * 3] = rgba[i * 4];
+      rgb[i * 3 + 1] = rgba[i * 4 + 1];
+      rgb[i * 3 + 2] = rgba[i * 4 + 2];
+      alpha[i] = rgba[i * 4 + 3];
+    }
+    wxIcon icon;
+    icon.CopyFromBitmap(wxBitmap(image));
+    bundle->AddIcon(icon);
+  }
+
+  // Every image in the file is added, so an ICO can bring several sizes at once
+  bool add_file_to_icon_bundle(wxIconBundle *bundle, unsigned char *bytes, unsigned int len) {
+    // Don't pop up an error dialog for a file that can't be read
+    wxLogNull noLog;
+    size_t count = bundle->GetIconCount();
+    wxMemoryInputStream stream(bytes, len);
+    bundle->AddIcon(stream);
+    return bundle->GetIconCount() > count;
+  }
+
+  unsigned int get_icon_bundle_count(wxIconBundle *bundle) {
+    return bundle->GetIconCount();
+  }
+
+  Size get_icon_bundle_size(wxIconBundle *bundle, unsigned int i) {
+    if (i >= bundle->GetIconCount()) {
+      return Size {0, 0};
+    }
+    wxIcon icon = bundle->GetIconByIndex(i);
+    return Size {icon.GetWidth(), icon.GetHeight()};
+  }
+
+  void delete_icon_bundle(wxIconBundle *bundle) {
+    delete bundle;
+  }
+
+  // The frame keeps its own copy of the icons
+  void set_icon(int window, wxIconBundle *bundle) {
+    MyFrame *frame = wxGetApp().FindFrame(window);
+    if (frame) {
+      frame->SetIcons(*bundle);
+    }
+  }
+
+  // Optional components. These only exist with their feature, and return whether the component
+  // can be used
+#ifdef WXRS_USE_AUI
+  bool init_aui() {
+    // wxAUI needs no setting up
+    return true;
+  }
+#endif
+#ifdef WXRS_USE_GL
+  bool init_gl() {
+    wxGLAttributes attributes;
+    attributes.PlatformDefaults().Defaults().EndList();
+    return wxGLCanvas::IsDisplaySupported(attributes);
+  }
+#endif
+#ifdef WXRS_USE_HTML
+  bool init_html() {
+    // wxHtmlWindow needs no setting up
+    return true;
+  }
+#endif
+#ifdef WXRS_USE_PROPGRID
+  bool init_propgrid() {
+    // wxPropertyGrid needs no setting up
+    return true;
+  }
+#endif
+#ifdef WXRS_USE_RIBBON
+  bool init_ribbon() {
+    // wxRibbonBar needs no setting up
+    return true;
+  }
+#endif
+#ifdef WXRS_USE_RICHTEXT
+  // Lets wxRichTextCtrl load and save its XML and HTML formats. Adding the handlers again is
+  // harmless, they're looked up by type first
+  bool init_richtext() {
+    wxRichTextBuffer::InitStandardHandlers();
+    return true;
+  }
+#endif
+#ifdef WXRS_USE_STC
+  bool init_stc() {
+    // wxStyledTextCtrl needs no setting up
+    return true;
+  }
+#endif
+#ifdef WXRS_USE_XRC
+  // Lets resources be loaded for every control. Handlers would be added twice if called again
+  bool init_xrc() {
+    static bool initialized = false;
+    if (!initialized) {
+      wxXmlResource::Get()->InitAllHandlers();
+      initialized = true;
+    }
+    return true;
+  }
+#endif
 }
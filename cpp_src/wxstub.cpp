@@ -0,0 +1,185 @@
+// A no-op implementation of the bridge, for platforms that wxWidgets isn't built on.
+// Every function declared in `bridge::notsafe` is defined here with a harmless default,
+// so that crates depending on wx-rs can still link and run headless.
+#include <stddef.h>
+
+// Handed out in place of real wxMenu/wxMenuBar/wxIconBundle pointers
+static char dummy_menu;
+static char dummy_menu_bar;
+static char dummy_icon_bundle;
+
+extern "C" {
+
+  struct Size {
+    int x;
+    int y;
+  };
+
+  struct WindowsHandle {
+    void *hwnd;
+    void *hinstance;
+  };
+
+  struct OSXHandle {
+    void *ns_window;
+    void *ns_view;
+  };
+
+  struct X11Handle {
+    unsigned long window;
+    void *display;
+    int screen;
+  };
+
+  struct WindowOptions {
+    char *title;
+    unsigned int width;
+    unsigned int height;
+    bool has_position;
+    int x;
+    int y;
+    int min_width;
+    int min_height;
+    int max_width;
+    int max_height;
+    bool resizable;
+    bool borderless;
+    bool always_on_top;
+    bool tool_window;
+    bool visible;
+  };
+
+  void init_app(WindowOptions) {}
+  void set_render(void (*)(int)) {}
+  void run_app() {}
+  void close_app() {}
+
+  // Windows
+  int get_main_window() { return 0; }
+  int create_window(WindowOptions) { return 0; }
+  void close_window(int) {}
+  void veto_close(int) {}
+  void request_close(int) {}
+  void set_title(int, char *) {}
+  void set_size(int, unsigned int, unsigned int) {}
+  void set_position(int, Size) {}
+  void set_visible(int, bool) {}
+  void set_fullscreen(int, bool) {}
+  void maximize(int) {}
+  void minimize(int) {}
+  void restore(int) {}
+  bool is_fullscreen(int) { return false; }
+  bool is_maximized(int) { return false; }
+  bool is_minimized(int) { return false; }
+  void refresh(int) {}
+
+  Size get_client_size(int) { return Size { 0, 0 }; }
+  Size get_display_size(int) { return Size { 0, 0 }; }
+  float get_scale_factor(int) { return 1.0; }
+
+  WindowsHandle get_windows_raw_window_handle(int) { return WindowsHandle { NULL, NULL }; }
+  OSXHandle get_osx_raw_window_handle(int) { return OSXHandle { NULL, NULL }; }
+  bool is_wayland() { return false; }
+  X11Handle get_x11_raw_window_handle(int) { return X11Handle { 0, NULL, 0 }; }
+
+  // Events
+  void bind_canvas_events(void (*)(int, void *)) {}
+  int get_event_type(void *) { return 0; }
+  int get_event_id(void *) { return 0; }
+  int get_event_key(void *) { return 0; }
+  unsigned int get_raw_key_code(void *) { return 0; }
+  unsigned int get_native_scan_code(void *) { return 0; }
+  bool is_auto_repeat(void *) { return false; }
+  unsigned int get_event_char(void *) { return 0; }
+  int get_modifiers(void *) { return 0; }
+  int get_mouse_modifiers(void *) { return 0; }
+  int get_keyboard_state() { return 0; }
+  Size get_move_position(void *) { return Size { 0, 0 }; }
+  bool get_event_focused(void *) { return false; }
+  bool get_event_iconized(void *) { return false; }
+  bool get_event_fullscreen(void *) { return false; }
+  Size get_mouse_position(void *) { return Size { 0, 0 }; }
+  Size get_relative_motion(void *) { return Size { 0, 0 }; }
+  Size get_gesture_position(void *) { return Size { 0, 0 }; }
+  bool is_gesture_start(void *) { return false; }
+  bool is_gesture_end(void *) { return false; }
+  Size get_pan_delta(void *) { return Size { 0, 0 }; }
+  float get_zoom_factor(void *) { return 1.0; }
+  float get_rotation_angle(void *) { return 0.0; }
+  int get_mouse_wheel_rotation(void *) { return 0; }
+  int get_mouse_wheel_axis(void *) { return 0; }
+  int get_mouse_wheel_delta(void *) { return 0; }
+  int get_mouse_wheel_lines_per_action(void *) { return 0; }
+  int get_mouse_wheel_columns_per_action(void *) { return 0; }
+  bool is_mouse_wheel_inverted(void *) { return false; }
+  bool is_mouse_wheel_precise(void *) { return false; }
+  float get_magnification(void *) { return 0.0; }
+  int get_ime_text_len(void *) { return 0; }
+  void get_ime_text(void *, char *) {}
+  int get_ime_cursor(void *) { return -1; }
+
+  // Input methods
+  struct Rect {
+    int x;
+    int y;
+    unsigned int width;
+    unsigned int height;
+  };
+
+  void set_ime_cursor_area(int, Rect) {}
+  void set_ime_allowed(int, bool) {}
+
+  // Cursor
+  void set_cursor(int, int) {}
+
+  // Mouse
+  void capture_mouse(int) {}
+  void release_mouse(int) {}
+  void set_relative_mouse_mode(int, bool) {}
+  Size get_pointer_position(int) { return Size { 0, 0 }; }
+  void warp_pointer(int, Size) {}
+
+  // Status bar
+  void create_status_bar(int) {}
+  void set_status_text(int, char *) {}
+
+  // Clipboard
+  void put_string_on_clipboard(char *) {}
+  int get_clipboard_string_len() { return -1; }
+  void get_string_from_clipboard(char *) {}
+  void put_buffer_on_clipboard(void *, int) {}
+  int get_clipboard_buffer_len() { return -1; }
+  void get_buffer_from_clipboard(char *) {}
+
+  // Menus
+  void *create_menu() { return (void *) &dummy_menu; }
+  void insert_separator_to_menu(void *, size_t) {}
+  int insert_to_menu(void *, size_t, char *, char *) { return 0; }
+  void insert_submenu(void *, size_t, void *, char *, char *) {}
+  void remove_from_menu(void *, size_t) {}
+  void enable_menu_item(void *, size_t, bool) {}
+  void set_status_menu(int, void *) {}
+  void delete_menu(void *) {}
+  void *create_menu_bar() { return (void *) &dummy_menu_bar; }
+  void insert_to_menu_bar(void *, void *, size_t, char *) {}
+  void remove_from_menu_bar(void *, size_t) {}
+  void set_menu_bar(int, void *) {}
+  void delete_menu_bar(void *) {}
+
+  // Icons
+  void *create_icon_bundle() { return (void *) &dummy_icon_bundle; }
+  void add_rgba_to_icon_bundle(void *, unsigned int, unsigned int, unsigned char *) {}
+  bool add_file_to_icon_bundle(void *, unsigned char *, unsigned int) { return false; }
+  unsigned int get_icon_bundle_count(void *) { return 0; }
+  Size get_icon_bundle_size(void *, unsigned int) { return Size { 0, 0 }; }
+  void delete_icon_bundle(void *) {}
+  void set_icon(int, void *) {}
+  bool init_aui() { return false; }
+  bool init_gl() { return false; }
+  bool init_html() { return false; }
+  bool init_propgrid() { return false; }
+  bool init_ribbon() { return false; }
+  bool init_richtext() { return false; }
+  bool init_stc() { return false; }
+  bool init_xrc() { return false; }
+}
@@ -1,33 +1,32 @@
 use std::io::{self, Write};
-use wx_rs;
-
-extern "C" fn render() {
-    print!(".");
-    io::stdout().flush().unwrap();
-}
-
-use std::os::raw::c_void;
-extern "C" fn handle_event(event: *const c_void) {
-    match wx_rs::get_event_type(event) {
-        e if e != wx_rs::EventType::Timer => {
-            wx_rs::set_status_text(&format!("Got event: {:?}", e));
-        }
-        _ => (),
-    }
-}
+use wx_rs::{self, Event, Window};
 
 fn main() {
     println!("hello");
     wx_rs::init_app("Hello!", 400, 300);
+    let window = Window::main();
     println!(
         "initialized window with surface size {:?}, display rect {:?} at scale {}.",
-        wx_rs::get_client_size(),
-        wx_rs::get_display_size(),
-        wx_rs::get_scale_factor(),
+        window.get_client_size(),
+        window.get_display_size(),
+        window.get_scale_factor(),
     );
-    wx_rs::set_render(render);
-    wx_rs::bind_canvas_events(handle_event);
-    wx_rs::create_status_bar();
+
+    let mut frames = 0;
+    wx_rs::set_render(move |_| {
+        frames += 1;
+        if frames % 100 == 0 {
+            print!(".");
+            io::stdout().flush().unwrap();
+        }
+    });
+    wx_rs::on_event(|id, event| match event {
+        Event::Timer => (),
+        e => {
+            Window::from_id(id).set_status_text(&format!("Got event: {:?}", e));
+        }
+    });
+    window.create_status_bar();
 
     wx_rs::run_app();
 
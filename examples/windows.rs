@@ -0,0 +1,27 @@
+use wx_rs::{self, Event, MouseButton, Window, WindowBuilder};
+
+fn main() {
+    wx_rs::init_app("Main window", 400, 300);
+    let main = Window::main();
+    main.create_status_bar();
+    main.set_status_text("Click to open another window");
+
+    let mut opened = 0;
+    wx_rs::on_event(move |id, event| match event {
+        Event::MouseUp {
+            button: MouseButton::Left,
+            ..
+        } if id == main.id() => {
+            opened += 1;
+            let builder = WindowBuilder::new(&format!("Window {}", opened))
+                .size(300, 200)
+                .tool_window(true);
+            let window = Window::new(builder);
+            window.create_status_bar();
+        }
+        Event::Timer => (),
+        e => Window::from_id(id).set_status_text(&format!("Got event: {:?}", e)),
+    });
+
+    wx_rs::run_app();
+}
@@ -1,29 +1,70 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
 use std::ffi::CString;
-use std::os::raw::c_void;
+use std::os::raw::{c_int, c_void};
+use std::panic::{self, AssertUnwindSafe};
 
 use raw_window_handle::{
     HasRawDisplayHandle, HasRawWindowHandle, RawDisplayHandle, RawWindowHandle,
 };
 
-mod notsafe {
-    use std::os::raw::{c_char, c_int, c_void};
+use super::event::{Event, Gesture};
+use super::icon::Icon;
+use super::key::{KeyCode, ScanCode};
+use super::menu::{Menu, MenuBar};
+
+pub(crate) mod notsafe {
+    use std::os::raw::{c_char, c_int, c_ulong, c_void};
 
     use bitflags::bitflags;
 
-    #[derive(Debug)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     #[repr(C)]
     pub struct Size {
         pub width: u32,
         pub height: u32,
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     #[repr(C)]
     pub struct Point {
         pub x: c_int,
         pub y: c_int,
     }
 
+    /// A rectangle in logical pixels, from the top left of the canvas
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    #[repr(C)]
+    pub struct Rect {
+        pub x: c_int,
+        pub y: c_int,
+        pub width: u32,
+        pub height: u32,
+    }
+
+    /// See `WindowBuilder`
+    #[repr(C)]
+    pub struct WindowOptions {
+        pub title: *const c_char,
+        /// 0 for the platform's default
+        pub width: u32,
+        pub height: u32,
+        pub has_position: bool,
+        pub x: c_int,
+        pub y: c_int,
+        /// -1 for no limit
+        pub min_width: c_int,
+        pub min_height: c_int,
+        pub max_width: c_int,
+        pub max_height: c_int,
+        pub resizable: bool,
+        pub borderless: bool,
+        pub always_on_top: bool,
+        pub tool_window: bool,
+        pub visible: bool,
+    }
+
     #[repr(C)]
     #[allow(dead_code)]
     pub struct WindowsHandle {
@@ -38,6 +79,14 @@ mod notsafe {
         pub ns_view: *mut c_void,
     }
 
+    #[repr(C)]
+    #[allow(dead_code)]
+    pub struct X11Handle {
+        pub window: c_ulong,
+        pub display: *mut c_void,
+        pub screen: c_int,
+    }
+
     #[repr(u32)]
     #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     pub enum EventType {
@@ -69,15 +118,38 @@ mod notsafe {
         Timer,
         Exit,
         Menu,
+        TextInput,
+        CompositionStart,
+        CompositionUpdate,
+        CompositionCommit,
+        ImeTextInput,
+        MouseCaptureLost,
+        RelativeMotion,
+        GesturePan,
+        GestureZoom,
+        GestureRotate,
+        TwoFingerTap,
+        LongPress,
+        PressAndTap,
+        Magnify,
+        Iconize,
+        Maximize,
+        FullScreen,
+        CloseRequested,
     }
 
     bitflags! {
+        /// The lock keys are only reported by `keyboard_state`, so that comparing the
+        /// modifiers of an event isn't thrown off by num lock being on
         pub struct Modifiers: u32 {
-            const NONE    = 0b0000;
-            const ALT     = 0b0001;
-            const CONTROL = 0b0010;
-            const SHIFT   = 0b0100;
-            const META    = 0b1000;
+            const NONE        = 0b0000;
+            const ALT         = 0b0001;
+            const CONTROL     = 0b0010;
+            const SHIFT       = 0b0100;
+            const META        = 0b1000;
+            const CAPS_LOCK   = 0b0001_0000_0000;
+            const NUM_LOCK    = 0b0010_0000_0000;
+            const SCROLL_LOCK = 0b0100_0000_0000;
         }
     }
 
@@ -112,38 +184,94 @@ mod notsafe {
         Horizontal,
     }
 
+    #[cfg(feature = "mock")]
+    pub use crate::mock::ffi::*;
+
+    #[cfg(not(feature = "mock"))]
     #[link(name = "wxbridge")]
     extern "C" {
-        pub fn init_app(name: *const c_char, width: u32, height: u32);
-        pub fn set_render(render: extern "C" fn());
+        pub fn init_app(options: WindowOptions);
+        pub fn set_render(render: extern "C" fn(window: c_int));
         pub fn run_app();
         pub fn close_app();
-        pub fn refresh();
-        pub fn get_client_size() -> Size;
-        pub fn get_display_size() -> Size;
-        pub fn get_scale_factor() -> f32;
+        // Windows
+        pub fn get_main_window() -> c_int;
+        pub fn create_window(options: WindowOptions) -> c_int;
+        pub fn close_window(window: c_int);
+        pub fn veto_close(window: c_int);
+        pub fn request_close(window: c_int);
+        pub fn set_title(window: c_int, title: *const c_char);
+        pub fn set_size(window: c_int, width: u32, height: u32);
+        pub fn set_position(window: c_int, position: Point);
+        pub fn set_visible(window: c_int, visible: bool);
+        pub fn set_fullscreen(window: c_int, fullscreen: bool);
+        pub fn maximize(window: c_int);
+        pub fn minimize(window: c_int);
+        pub fn restore(window: c_int);
+        pub fn is_fullscreen(window: c_int) -> bool;
+        pub fn is_maximized(window: c_int) -> bool;
+        pub fn is_minimized(window: c_int) -> bool;
+        pub fn refresh(window: c_int);
+        pub fn get_client_size(window: c_int) -> Size;
+        pub fn get_display_size(window: c_int) -> Size;
+        pub fn get_scale_factor(window: c_int) -> f32;
+        #[allow(dead_code)]
+        pub fn get_windows_raw_window_handle(window: c_int) -> WindowsHandle;
         #[allow(dead_code)]
-        pub fn get_windows_raw_window_handle() -> WindowsHandle;
+        pub fn get_osx_raw_window_handle(window: c_int) -> OSXHandle;
         #[allow(dead_code)]
-        pub fn get_osx_raw_window_handle() -> OSXHandle;
+        pub fn is_wayland() -> bool;
+        #[allow(dead_code)]
+        pub fn get_x11_raw_window_handle(window: c_int) -> X11Handle;
         // Events
-        pub fn bind_canvas_events(handle_event: extern "C" fn(event: *const c_void));
+        pub fn bind_canvas_events(handle_event: extern "C" fn(window: c_int, event: *const c_void));
         pub fn get_event_type(event: *const c_void) -> EventType;
         pub fn get_event_key(key_event: *const c_void) -> c_int;
+        pub fn get_raw_key_code(key_event: *const c_void) -> u32;
+        pub fn get_native_scan_code(key_event: *const c_void) -> u32;
+        pub fn is_auto_repeat(key_event: *const c_void) -> bool;
         pub fn get_event_char(key_event: *const c_void) -> u32;
         pub fn get_modifiers(key_event: *const c_void) -> u32;
-        pub fn shift_down(key_event: *const c_void) -> bool;
+        pub fn get_mouse_modifiers(mouse_event: *const c_void) -> u32;
+        pub fn get_keyboard_state() -> u32;
+        pub fn get_move_position(move_event: *const c_void) -> Point;
         pub fn get_event_focused(focus_event: *const c_void) -> bool;
+        pub fn get_event_iconized(iconize_event: *const c_void) -> bool;
+        pub fn get_event_fullscreen(fullscreen_event: *const c_void) -> bool;
         pub fn get_event_id(menu_event: *const c_void) -> i32;
         pub fn get_mouse_position(mouse_event: *const c_void) -> Point;
+        pub fn get_relative_motion(relative_event: *const c_void) -> Point;
+        pub fn get_gesture_position(gesture_event: *const c_void) -> Point;
+        pub fn is_gesture_start(gesture_event: *const c_void) -> bool;
+        pub fn is_gesture_end(gesture_event: *const c_void) -> bool;
+        pub fn get_pan_delta(pan_event: *const c_void) -> Point;
+        pub fn get_zoom_factor(zoom_event: *const c_void) -> f32;
+        pub fn get_rotation_angle(rotate_event: *const c_void) -> f32;
         pub fn get_mouse_wheel_rotation(mouse_event: *const c_void) -> c_int;
         pub fn get_mouse_wheel_delta(mouse_event: *const c_void) -> c_int;
         pub fn get_mouse_wheel_axis(mouse_event: *const c_void) -> WheelAxis;
+        pub fn get_mouse_wheel_lines_per_action(mouse_event: *const c_void) -> c_int;
+        pub fn get_mouse_wheel_columns_per_action(mouse_event: *const c_void) -> c_int;
+        pub fn is_mouse_wheel_inverted(mouse_event: *const c_void) -> bool;
+        pub fn is_mouse_wheel_precise(mouse_event: *const c_void) -> bool;
+        pub fn get_magnification(mouse_event: *const c_void) -> f32;
+        pub fn get_ime_text_len(ime_event: *const c_void) -> c_int;
+        pub fn get_ime_text(ime_event: *const c_void, text: *mut u8);
+        pub fn get_ime_cursor(ime_event: *const c_void) -> c_int;
+
+        pub fn set_ime_cursor_area(window: c_int, area: Rect);
+        pub fn set_ime_allowed(window: c_int, allowed: bool);
         // Cursor
-        pub fn set_cursor(cursor: CursorType);
+        pub fn set_cursor(window: c_int, cursor: CursorType);
+
+        pub fn capture_mouse(window: c_int);
+        pub fn release_mouse(window: c_int);
+        pub fn set_relative_mouse_mode(window: c_int, enabled: bool);
+        pub fn get_pointer_position(window: c_int) -> Point;
+        pub fn warp_pointer(window: c_int, position: Point);
         // Status Bar
-        pub fn set_status_text(cursor: *const c_char);
-        pub fn create_status_bar();
+        pub fn set_status_text(window: c_int, cursor: *const c_char);
+        pub fn create_status_bar(window: c_int);
         // Clipboard
         pub fn put_string_on_clipboard(string: *const c_char);
         pub fn put_buffer_on_clipboard(buf: *const u8, len: u32);
@@ -169,7 +297,7 @@ mod notsafe {
         pub fn insert_separator_to_menu(menu: *const c_void, i: u32);
         pub fn remove_from_menu(menu: *const c_void, i: u32);
         pub fn enable_menu_item(menu: *const c_void, i: u32, enable: bool);
-        pub fn set_status_menu(menu: *const c_void);
+        pub fn set_status_menu(window: c_int, menu: *const c_void);
         pub fn delete_menu(menu: *const c_void);
         pub fn create_menu_bar() -> *const c_void;
         pub fn insert_to_menu_bar(
@@ -179,8 +307,37 @@ mod notsafe {
             entry: *const c_char,
         );
         pub fn remove_from_menu_bar(menu_bar: *const c_void, i: u32);
-        pub fn set_menu_bar(menu_bar: *const c_void);
+        pub fn set_menu_bar(window: c_int, menu_bar: *const c_void);
         pub fn delete_menu_bar(menu_bar: *const c_void);
+        // Icons
+        pub fn create_icon_bundle() -> *const c_void;
+        pub fn add_rgba_to_icon_bundle(
+            bundle: *const c_void,
+            width: u32,
+            height: u32,
+            rgba: *const u8,
+        );
+        pub fn add_file_to_icon_bundle(bundle: *const c_void, bytes: *const u8, len: u32) -> bool;
+        pub fn get_icon_bundle_count(bundle: *const c_void) -> u32;
+        pub fn get_icon_bundle_size(bundle: *const c_void, i: u32) -> Size;
+        pub fn delete_icon_bundle(bundle: *const c_void);
+        pub fn set_icon(window: c_int, bundle: *const c_void);
+        #[cfg(feature = "aui")]
+        pub fn init_aui() -> bool;
+        #[cfg(feature = "opengl")]
+        pub fn init_gl() -> bool;
+        #[cfg(feature = "html")]
+        pub fn init_html() -> bool;
+        #[cfg(feature = "propgrid")]
+        pub fn init_propgrid() -> bool;
+        #[cfg(feature = "ribbon")]
+        pub fn init_ribbon() -> bool;
+        #[cfg(feature = "richtext")]
+        pub fn init_richtext() -> bool;
+        #[cfg(feature = "stc")]
+        pub fn init_stc() -> bool;
+        #[cfg(feature = "xrc")]
+        pub fn init_xrc() -> bool;
     }
 }
 
@@ -188,25 +345,34 @@ pub use notsafe::CursorType;
 pub use notsafe::EventType;
 pub use notsafe::Modifiers;
 pub use notsafe::Point;
+pub use notsafe::Rect;
 pub use notsafe::Size;
 pub use notsafe::WheelAxis;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Data {
     String(String),
     Custom(Vec<u8>),
 }
 
 pub fn init_app(name: &str, width: u32, height: u32) {
-    unsafe {
-        let s = CString::new(name).unwrap();
-        notsafe::init_app(s.as_ptr(), width, height);
-    };
+    init_app_with(WindowBuilder::new(name).size(width, height));
+}
+
+/// Like `init_app`, with more control over the main window
+pub fn init_app_with(window: WindowBuilder) {
+    window.with_options(|options| unsafe { notsafe::init_app(options) });
 }
 
+/// Runs the event loop until the app is closed.
+/// If a callback panicked, the app is closed and the panic is resumed here.
 pub fn run_app() {
     unsafe {
         notsafe::run_app();
     };
+    if let Some(payload) = PANIC.with(|p| p.borrow_mut().take()) {
+        panic::resume_unwind(payload);
+    }
 }
 
 pub fn close_app() {
@@ -215,32 +381,178 @@ pub fn close_app() {
     };
 }
 
-pub fn refresh() {
+/// Called whenever a window needs to be drawn, and on every tick of each window's timer
+pub fn set_render(render: impl FnMut(WindowId) + 'static) {
+    RENDER.with(|r| r.set(Box::new(render)));
     unsafe {
-        notsafe::refresh();
+        notsafe::set_render(render_trampoline);
     };
 }
 
-pub fn set_render(render: extern "C" fn()) {
-    unsafe {
-        notsafe::set_render(render);
-    };
+type Slot<T> = RefCell<Option<Box<dyn FnMut(T)>>>;
+
+struct Callback<T> {
+    callback: Slot<T>,
+    running: Cell<bool>,
+    pending: RefCell<VecDeque<T>>,
+}
+
+impl<T> Callback<T> {
+    const fn new() -> Self {
+        Callback {
+            callback: RefCell::new(None),
+            running: Cell::new(false),
+            pending: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    fn set(&self, callback: Box<dyn FnMut(T)>) {
+        *self.callback.borrow_mut() = Some(callback);
+    }
+}
+
+/// Resets a `Callback` that was running, even if the callback panicked
+struct Running<'a, T>(&'a Callback<T>);
+
+impl<T> Drop for Running<'_, T> {
+    fn drop(&mut self) {
+        self.0.running.set(false);
+        self.0.pending.borrow_mut().clear();
+    }
+}
+
+thread_local!(
+    static RENDER: Callback<WindowId> = const { Callback::new() };
+    static EVENT_HANDLER: Callback<(WindowId, Event)> = const { Callback::new() };
+    static EVENTS_BOUND: Cell<bool> = const { Cell::new(false) };
+    static DEFERRED_CLOSES: RefCell<Vec<WindowId>> = const { RefCell::new(Vec::new()) };
+    static PANIC: RefCell<Option<Box<dyn Any + Send>>> = const { RefCell::new(None) };
+);
+
+/// Forget the callbacks, and any panic one of them left behind, so that the mock backend can
+/// start over on the same thread
+#[cfg(feature = "mock")]
+pub(crate) fn reset_callbacks() {
+    RENDER.with(|r| *r.callback.borrow_mut() = None);
+    EVENT_HANDLER.with(|h| *h.callback.borrow_mut() = None);
+    EVENTS_BOUND.with(|b| b.set(false));
+    DEFERRED_CLOSES.with(|c| c.borrow_mut().clear());
+    PANIC.with(|p| *p.borrow_mut() = None);
+}
+
+/// Call the callback in `cell`, if there is one.
+/// Anything the callback triggers re-entrantly is queued rather than aliasing it, and is
+/// handled in order once the callback returns. A queued argument that `supersedes` says the
+/// new one replaces is overwritten rather than queued again. The callback is taken out of its
+/// cell while it runs, and is put back afterwards unless it was replaced in the meantime.
+fn call<T>(
+    cell: &'static std::thread::LocalKey<Callback<T>>,
+    arg: T,
+    supersedes: fn(&T, &T) -> bool,
+) {
+    cell.with(|c| {
+        {
+            let mut pending = c.pending.borrow_mut();
+            match pending.iter_mut().find(|queued| supersedes(&arg, queued)) {
+                Some(queued) => *queued = arg,
+                None => pending.push_back(arg),
+            }
+        }
+        if c.running.replace(true) {
+            return;
+        }
+        let _running = Running(c);
+        loop {
+            let Some(arg) = c.pending.borrow_mut().pop_front() else {
+                break;
+            };
+            let Some(mut callback) = c.callback.borrow_mut().take() else {
+                continue;
+            };
+            callback(arg);
+            let mut slot = c.callback.borrow_mut();
+            if slot.is_none() {
+                *slot = Some(callback);
+            }
+        }
+    });
 }
 
-pub fn get_client_size() -> Size {
-    unsafe { notsafe::get_client_size() }
+/// Unwinding into the bridge is undefined behaviour, so panics are caught before they reach it.
+/// The app is then closed, and the panic is resumed by `run_app`. No more callbacks are run
+/// while that happens.
+fn catch_panic(f: impl FnOnce()) {
+    if PANIC.with(|p| p.borrow().is_some()) {
+        return;
+    }
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(f)) {
+        PANIC.with(|p| *p.borrow_mut() = Some(payload));
+        close_app();
+    }
 }
 
-pub fn get_display_size() -> Size {
-    unsafe { notsafe::get_display_size() }
+extern "C" fn render_trampoline(window: c_int) {
+    catch_panic(|| dispatch_render(WindowId(window)));
 }
 
-pub fn get_scale_factor() -> f32 {
-    unsafe { notsafe::get_scale_factor() }
+/// The only function the bridge ever calls with an event, so that `Event`s are built in one place
+extern "C" fn handle_event_trampoline(window: c_int, event: *const c_void) {
+    let window = WindowId(window);
+    catch_panic(|| dispatch_event(window, unsafe { Event::from_raw(window, event) }));
 }
 
-pub fn bind_canvas_events(handle_event: extern "C" fn(event: *const c_void)) {
-    unsafe { notsafe::bind_canvas_events(handle_event) };
+pub(crate) fn dispatch_render(window: WindowId) {
+    call(&RENDER, window, |window, queued| window == queued);
+}
+
+pub(crate) fn dispatch_event(window: WindowId, event: Event) {
+    if event == Event::CloseRequested && EVENT_HANDLER.with(|h| h.running.get()) {
+        // The bridge decides whether to close as soon as this returns, before a queued
+        // `CloseRequested` would reach the handler. So the close is turned down, and asked for
+        // again once the handler is free to veto it
+        unsafe { notsafe::veto_close(window.0) };
+        DEFERRED_CLOSES.with(|c| {
+            let mut deferred = c.borrow_mut();
+            if !deferred.contains(&window) {
+                deferred.push(window);
+            }
+        });
+        return;
+    }
+    call(&EVENT_HANDLER, (window, event), supersedes);
+    if !EVENT_HANDLER.with(|h| h.running.get()) {
+        for window in DEFERRED_CLOSES.with(|c| c.take()) {
+            unsafe { notsafe::request_close(window.0) };
+        }
+    }
+}
+
+/// Events that only matter in their latest form, so that a handler that blocks for a while, e.g.
+/// on a modal dialog, isn't sent a backlog of them once it returns
+fn supersedes(
+    (window, event): &(WindowId, Event),
+    (queued_window, queued): &(WindowId, Event),
+) -> bool {
+    window == queued_window
+        && matches!(
+            (event, queued),
+            (Event::Timer, Event::Timer)
+                | (Event::Resized(_), Event::Resized(_))
+                | (Event::Moved(_), Event::Moved(_))
+        )
+}
+
+/// Handle the events of every window, which are given along with the ID of the window they
+/// came from. Replaces any handler that was previously set.
+///
+/// Events that arrive while the handler is running, e.g. from a call it makes or from a modal
+/// dialog it shows, are queued and sent in order once it returns. Only the latest `Timer`,
+/// `Resized` and `Moved` of each window are kept in the queue
+pub fn on_event(mut handle_event: impl FnMut(WindowId, Event) + 'static) {
+    EVENT_HANDLER.with(|h| h.set(Box::new(move |(window, event)| handle_event(window, event))));
+    if !EVENTS_BOUND.with(|b| b.replace(true)) {
+        unsafe { notsafe::bind_canvas_events(handle_event_trampoline) };
+    }
 }
 
 // Clipboard
@@ -283,107 +595,194 @@ pub fn get_from_clipboard() -> Option<Data> {
 }
 
 // Events
-pub fn get_event_type(event: *const c_void) -> EventType {
+pub(crate) unsafe fn get_event_type(event: *const c_void) -> EventType {
     unsafe { notsafe::get_event_type(event) }
 }
 
-pub fn get_event_key(key_event: *const c_void) -> i32 {
-    unsafe { notsafe::get_event_key(key_event) }
+pub(crate) unsafe fn get_event_key(key_event: *const c_void) -> KeyCode {
+    KeyCode::from(unsafe { notsafe::get_event_key(key_event) })
+}
+
+pub(crate) unsafe fn get_scan_code(key_event: *const c_void) -> ScanCode {
+    ScanCode::from_native(unsafe { notsafe::get_native_scan_code(key_event) })
+}
+
+pub(crate) unsafe fn get_raw_key_code(key_event: *const c_void) -> u32 {
+    unsafe { notsafe::get_raw_key_code(key_event) }
+}
+
+pub(crate) unsafe fn is_auto_repeat(key_event: *const c_void) -> bool {
+    unsafe { notsafe::is_auto_repeat(key_event) }
+}
+
+// The optional components' entry points, for when the app's own native code uses them. Call them
+// after `init_app`; each returns whether its component can be used
+
+/// Sets up wxAUI
+#[cfg(feature = "aui")]
+pub fn init_aui() -> bool {
+    unsafe { notsafe::init_aui() }
+}
+
+/// Sets up wxGLCanvas, if the display supports OpenGL
+#[cfg(feature = "opengl")]
+pub fn init_gl() -> bool {
+    unsafe { notsafe::init_gl() }
+}
+
+/// Sets up wxHtmlWindow
+#[cfg(feature = "html")]
+pub fn init_html() -> bool {
+    unsafe { notsafe::init_html() }
+}
+
+/// Sets up wxPropertyGrid
+#[cfg(feature = "propgrid")]
+pub fn init_propgrid() -> bool {
+    unsafe { notsafe::init_propgrid() }
+}
+
+/// Sets up wxRibbonBar
+#[cfg(feature = "ribbon")]
+pub fn init_ribbon() -> bool {
+    unsafe { notsafe::init_ribbon() }
 }
 
-pub fn get_modifiers(key_event: *const c_void) -> Modifiers {
+/// Sets up wxRichTextCtrl, with its XML and HTML file handlers
+#[cfg(feature = "richtext")]
+pub fn init_richtext() -> bool {
+    unsafe { notsafe::init_richtext() }
+}
+
+/// Sets up wxStyledTextCtrl
+#[cfg(feature = "stc")]
+pub fn init_stc() -> bool {
+    unsafe { notsafe::init_stc() }
+}
+
+/// Sets up XRC resources, with handlers for every control
+#[cfg(feature = "xrc")]
+pub fn init_xrc() -> bool {
+    unsafe { notsafe::init_xrc() }
+}
+
+pub(crate) unsafe fn get_modifiers(key_event: *const c_void) -> Modifiers {
     unsafe { Modifiers::new(notsafe::get_modifiers(key_event)) }
 }
 
-pub fn get_event_string(key_event: *const c_void) -> Option<String> {
-    unsafe {
-        let c = notsafe::get_event_char(key_event);
-        if c == 0 || c == 8 || c == 9 || c == 13 || c == 27 || c == 127 {
-            // Ignore control keys, tab, backspace, enter, escape
-            None
-        } else if get_modifiers(key_event) - Modifiers::SHIFT != Modifiers::NONE {
-            // If there are non-shift modifiers, don't create a text event
-            None
-        } else {
-            if let Some(ch) = std::char::from_u32(c) {
-                if shift_down(key_event) {
-                    Some(match c {
-                        // Uppercase special characters are not returned by wxWidgets
-                        // <lowercase_special_key_char> => <String>
-                        44 => "<".to_string(),
-                        45 => "_".to_string(),
-                        46 => ">".to_string(),
-                        47 => "?".to_string(),
-                        48 => ")".to_string(),
-                        49 => "!".to_string(),
-                        50 => "@".to_string(),
-                        51 => "#".to_string(),
-                        52 => "$".to_string(),
-                        53 => "%".to_string(),
-                        54 => "^".to_string(),
-                        55 => "&".to_string(),
-                        56 => "*".to_string(),
-                        57 => "(".to_string(),
-                        59 => ":".to_string(),
-                        61 => "+".to_string(),
-                        91 => "{".to_string(),
-                        92 => "|".to_string(),
-                        93 => "}".to_string(),
-                        96 => "~".to_string(),
-                        _ => ch.to_string(),
-                    })
-                } else {
-                    // Lowercase text characters are not returned by wxWidgets
-                    Some(ch.to_lowercase().to_string())
-                }
-            } else {
-                None
-            }
-        }
-    }
+/// The modifier keys held down, and the lock keys that are on, right now
+pub fn keyboard_state() -> Modifiers {
+    unsafe { Modifiers::new(notsafe::get_keyboard_state()) }
+}
+
+pub(crate) unsafe fn get_mouse_modifiers(mouse_event: *const c_void) -> Modifiers {
+    unsafe { Modifiers::new(notsafe::get_mouse_modifiers(mouse_event)) }
+}
+
+pub(crate) unsafe fn get_move_position(move_event: *const c_void) -> Point {
+    unsafe { notsafe::get_move_position(move_event) }
 }
 
-pub fn shift_down(key_event: *const c_void) -> bool {
-    unsafe { notsafe::shift_down(key_event) }
+/// The text produced by a `wxEVT_CHAR`, which wxWidgets has already run through the keyboard layout
+pub(crate) unsafe fn get_event_text(char_event: *const c_void) -> Option<String> {
+    let modifiers = get_modifiers(char_event);
+    if modifiers.contains(Modifiers::CONTROL) && !modifiers.contains(Modifiers::ALT) {
+        // Shortcuts aren't text, though Ctrl+Alt is how AltGr is reported on Windows
+        return None;
+    }
+    let c = unsafe { notsafe::get_event_char(char_event) };
+    std::char::from_u32(c)
+        .filter(|c| !c.is_control())
+        .map(|c| c.to_string())
 }
 
-pub fn get_event_focused(focus_event: *const c_void) -> bool {
+pub(crate) unsafe fn get_event_focused(focus_event: *const c_void) -> bool {
     unsafe { notsafe::get_event_focused(focus_event) }
 }
 
-pub fn get_event_id(menu_event: *const c_void) -> i32 {
+pub(crate) unsafe fn get_event_iconized(iconize_event: *const c_void) -> bool {
+    unsafe { notsafe::get_event_iconized(iconize_event) }
+}
+
+pub(crate) unsafe fn get_event_fullscreen(fullscreen_event: *const c_void) -> bool {
+    unsafe { notsafe::get_event_fullscreen(fullscreen_event) }
+}
+
+pub(crate) unsafe fn get_event_id(menu_event: *const c_void) -> i32 {
     unsafe { notsafe::get_event_id(menu_event) }
 }
 
-pub fn get_mouse_position(mouse_event: *const c_void) -> Point {
+pub(crate) unsafe fn get_mouse_position(mouse_event: *const c_void) -> Point {
     unsafe { notsafe::get_mouse_position(mouse_event) }
 }
 
-pub fn get_mouse_wheel_rotation(mouse_event: *const c_void) -> i32 {
+pub(crate) unsafe fn get_relative_motion(relative_event: *const c_void) -> Point {
+    unsafe { notsafe::get_relative_motion(relative_event) }
+}
+
+pub(crate) unsafe fn get_gesture(gesture_event: *const c_void) -> Gesture {
+    unsafe {
+        Gesture {
+            position: notsafe::get_gesture_position(gesture_event),
+            start: notsafe::is_gesture_start(gesture_event),
+            end: notsafe::is_gesture_end(gesture_event),
+        }
+    }
+}
+
+pub(crate) unsafe fn get_pan_delta(pan_event: *const c_void) -> Point {
+    unsafe { notsafe::get_pan_delta(pan_event) }
+}
+
+pub(crate) unsafe fn get_zoom_factor(zoom_event: *const c_void) -> f32 {
+    unsafe { notsafe::get_zoom_factor(zoom_event) }
+}
+
+pub(crate) unsafe fn get_rotation_angle(rotate_event: *const c_void) -> f32 {
+    unsafe { notsafe::get_rotation_angle(rotate_event) }
+}
+
+pub(crate) unsafe fn get_mouse_wheel_rotation(mouse_event: *const c_void) -> i32 {
     unsafe { notsafe::get_mouse_wheel_rotation(mouse_event) }
 }
 
-pub fn get_mouse_wheel_delta(mouse_event: *const c_void) -> i32 {
+pub(crate) unsafe fn get_mouse_wheel_delta(mouse_event: *const c_void) -> i32 {
     unsafe { notsafe::get_mouse_wheel_delta(mouse_event) }
 }
 
-pub fn get_mouse_wheel_axis(mouse_event: *const c_void) -> WheelAxis {
+pub(crate) unsafe fn get_mouse_wheel_axis(mouse_event: *const c_void) -> WheelAxis {
     unsafe { notsafe::get_mouse_wheel_axis(mouse_event) }
 }
 
-// Cursor
-pub fn set_cursor(cursor: CursorType) {
-    unsafe { notsafe::set_cursor(cursor) }
+pub(crate) unsafe fn get_mouse_wheel_lines_per_action(mouse_event: *const c_void) -> i32 {
+    unsafe { notsafe::get_mouse_wheel_lines_per_action(mouse_event) }
+}
+
+pub(crate) unsafe fn get_mouse_wheel_columns_per_action(mouse_event: *const c_void) -> i32 {
+    unsafe { notsafe::get_mouse_wheel_columns_per_action(mouse_event) }
+}
+
+pub(crate) unsafe fn is_mouse_wheel_inverted(mouse_event: *const c_void) -> bool {
+    unsafe { notsafe::is_mouse_wheel_inverted(mouse_event) }
 }
 
-// Status bar
-pub fn set_status_text(text: &str) {
-    let s = CString::new(text).unwrap();
-    unsafe { notsafe::set_status_text(s.as_ptr()) }
+pub(crate) unsafe fn is_mouse_wheel_precise(mouse_event: *const c_void) -> bool {
+    unsafe { notsafe::is_mouse_wheel_precise(mouse_event) }
 }
 
-pub fn create_status_bar() {
-    unsafe { notsafe::create_status_bar() }
+pub(crate) unsafe fn get_magnification(mouse_event: *const c_void) -> f32 {
+    unsafe { notsafe::get_magnification(mouse_event) }
+}
+
+pub(crate) unsafe fn get_ime_text(ime_event: *const c_void) -> String {
+    let len = unsafe { notsafe::get_ime_text_len(ime_event) };
+    let mut dest: Vec<u8> = vec![0; len.max(0) as usize];
+    unsafe { notsafe::get_ime_text(ime_event, dest.as_mut_ptr()) };
+    String::from_utf8(dest).unwrap_or_default()
+}
+
+pub(crate) unsafe fn get_ime_cursor(ime_event: *const c_void) -> Option<usize> {
+    usize::try_from(unsafe { notsafe::get_ime_cursor(ime_event) }).ok()
 }
 
 // Menus
@@ -408,7 +807,7 @@ pub(crate) fn insert_to_menu(
             menu,
             i as u32,
             e.as_ptr(),
-            h.map_or(std::ptr::null(), |h| h.as_ptr()),
+            h.as_ref().map_or(std::ptr::null(), |h| h.as_ptr()),
         )
     }
 }
@@ -428,7 +827,7 @@ pub(crate) fn insert_submenu(
             i as u32,
             submenu,
             e.as_ptr(),
-            h.map_or(std::ptr::null(), |h| h.as_ptr()),
+            h.as_ref().map_or(std::ptr::null(), |h| h.as_ptr()),
         )
     }
 }
@@ -441,11 +840,11 @@ pub(crate) fn enable_menu_item(menu: *const c_void, i: usize, enable: bool) {
     unsafe { notsafe::enable_menu_item(menu, i as u32, enable) }
 }
 
-pub(crate) fn set_status_menu(menu: *const c_void) {
-    unsafe { notsafe::set_status_menu(menu) }
+pub(crate) fn set_status_menu(window: WindowId, menu: *const c_void) {
+    unsafe { notsafe::set_status_menu(window.0, menu) }
 }
 
-pub fn delete_menu(menu: *const c_void) {
+pub(crate) fn delete_menu(menu: *const c_void) {
     unsafe { notsafe::delete_menu(menu) }
 }
 
@@ -469,28 +868,395 @@ pub(crate) fn remove_from_menu_bar(menu_bar: *const c_void, i: usize) {
     unsafe { notsafe::remove_from_menu_bar(menu_bar, i as u32) }
 }
 
-pub fn set_menu_bar(menu_bar: *const c_void) {
-    unsafe { notsafe::set_menu_bar(menu_bar) }
+pub(crate) fn set_menu_bar(window: WindowId, menu_bar: *const c_void) {
+    unsafe { notsafe::set_menu_bar(window.0, menu_bar) }
 }
 
-pub fn delete_menu_bar(menu_bar: *const c_void) {
+pub(crate) fn delete_menu_bar(menu_bar: *const c_void) {
     unsafe { notsafe::delete_menu_bar(menu_bar) }
 }
 
+// Icons
+pub(crate) fn create_icon_bundle() -> *const c_void {
+    unsafe { notsafe::create_icon_bundle() }
+}
+
+/// `rgba` must hold `width * height` pixels
+pub(crate) fn add_rgba_to_icon_bundle(bundle: *const c_void, width: u32, height: u32, rgba: &[u8]) {
+    assert!(width > 0 && height > 0, "icon images can't be empty");
+    assert_eq!(rgba.len(), width as usize * height as usize * 4);
+    unsafe { notsafe::add_rgba_to_icon_bundle(bundle, width, height, rgba.as_ptr()) }
+}
+
+pub(crate) fn add_file_to_icon_bundle(bundle: *const c_void, bytes: &[u8]) -> bool {
+    unsafe { notsafe::add_file_to_icon_bundle(bundle, bytes.as_ptr(), bytes.len() as u32) }
+}
+
+pub(crate) fn get_icon_bundle_sizes(bundle: *const c_void) -> Vec<Size> {
+    unsafe {
+        (0..notsafe::get_icon_bundle_count(bundle))
+            .map(|i| notsafe::get_icon_bundle_size(bundle, i))
+            .collect()
+    }
+}
+
+pub(crate) fn delete_icon_bundle(bundle: *const c_void) {
+    unsafe { notsafe::delete_icon_bundle(bundle) }
+}
+
 // Window
-pub struct Window {}
+
+/// Identifies a window, and which window an event came from
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct WindowId(pub(crate) c_int);
+
+/// How to create a `Window`. Sizes are of the client area, in logical pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowBuilder {
+    title: String,
+    size: Option<Size>,
+    position: Option<Point>,
+    min_size: Option<Size>,
+    max_size: Option<Size>,
+    resizable: bool,
+    borderless: bool,
+    always_on_top: bool,
+    tool_window: bool,
+    visible: bool,
+}
+
+impl WindowBuilder {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            size: None,
+            position: None,
+            min_size: None,
+            max_size: None,
+            resizable: true,
+            borderless: false,
+            always_on_top: false,
+            tool_window: false,
+            visible: true,
+        }
+    }
+
+    /// The platform picks a size otherwise
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some(Size { width, height });
+        self
+    }
+
+    /// Where the top left of the window goes on the screen, in the same units as `Moved`. The
+    /// platform places it otherwise
+    pub fn position(mut self, position: Point) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// The smallest the user can make the window
+    pub fn min_size(mut self, width: u32, height: u32) -> Self {
+        self.min_size = Some(Size { width, height });
+        self
+    }
+
+    /// The largest the user can make the window
+    pub fn max_size(mut self, width: u32, height: u32) -> Self {
+        self.max_size = Some(Size { width, height });
+        self
+    }
+
+    /// Whether the user can resize and maximize the window. Defaults to true
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Without a title bar or border, so the user can't move or resize the window themselves
+    pub fn borderless(mut self, borderless: bool) -> Self {
+        self.borderless = borderless;
+        self
+    }
+
+    /// Keep the window above all others
+    pub fn always_on_top(mut self, always_on_top: bool) -> Self {
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    /// A palette or inspector: a smaller title bar on some platforms, and left out of the
+    /// taskbar
+    pub fn tool_window(mut self, tool_window: bool) -> Self {
+        self.tool_window = tool_window;
+        self
+    }
+
+    /// Whether the window is shown once it's created. Defaults to true. See `Window::set_visible`
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    fn with_options<R>(&self, f: impl FnOnce(notsafe::WindowOptions) -> R) -> R {
+        let title = CString::new(self.title.as_str()).unwrap();
+        let limit =
+            |size: Option<Size>| size.map_or((-1, -1), |s| (s.width as c_int, s.height as c_int));
+        let (min_width, min_height) = limit(self.min_size);
+        let (max_width, max_height) = limit(self.max_size);
+        let size = self.size.unwrap_or(Size {
+            width: 0,
+            height: 0,
+        });
+        let position = self.position.unwrap_or(Point { x: 0, y: 0 });
+        f(notsafe::WindowOptions {
+            title: title.as_ptr(),
+            width: size.width,
+            height: size.height,
+            has_position: self.position.is_some(),
+            x: position.x,
+            y: position.y,
+            min_width,
+            min_height,
+            max_width,
+            max_height,
+            resizable: self.resizable,
+            borderless: self.borderless,
+            always_on_top: self.always_on_top,
+            tool_window: self.tool_window,
+            visible: self.visible,
+        })
+    }
+
+    /// Safety: `options.title` must be a valid C string
+    #[cfg(feature = "mock")]
+    pub(crate) unsafe fn from_options(options: &notsafe::WindowOptions) -> Self {
+        let limit = |width: c_int, height: c_int| {
+            (width >= 0 && height >= 0).then_some(Size {
+                width: width as u32,
+                height: height as u32,
+            })
+        };
+        Self {
+            title: unsafe { std::ffi::CStr::from_ptr(options.title) }
+                .to_string_lossy()
+                .into_owned(),
+            size: (options.width > 0 && options.height > 0).then_some(Size {
+                width: options.width,
+                height: options.height,
+            }),
+            position: options.has_position.then_some(Point {
+                x: options.x,
+                y: options.y,
+            }),
+            min_size: limit(options.min_width, options.min_height),
+            max_size: limit(options.max_width, options.max_height),
+            resizable: options.resizable,
+            borderless: options.borderless,
+            always_on_top: options.always_on_top,
+            tool_window: options.tool_window,
+            visible: options.visible,
+        }
+    }
+}
+
+/// A top-level window. The first is created by `init_app`, and closing it closes the others
+/// and exits the app.
+///
+/// This is only a handle, so it can be copied freely. Once the window is closed, its methods do
+/// nothing and its sizes are zero
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Window {
+    id: WindowId,
+}
 
 impl Window {
-    pub fn new() -> Self {
-        Self {}
+    /// Create another window
+    pub fn new(builder: WindowBuilder) -> Self {
+        let id = builder.with_options(|options| unsafe { notsafe::create_window(options) });
+        Self { id: WindowId(id) }
+    }
+
+    /// The window created by `init_app`
+    pub fn main() -> Self {
+        Self::from_id(WindowId(unsafe { notsafe::get_main_window() }))
+    }
+
+    /// The window an event came from
+    pub fn from_id(id: WindowId) -> Self {
+        Self { id }
+    }
+
+    pub fn id(&self) -> WindowId {
+        self.id
+    }
+
+    /// Close the window without sending `CloseRequested`
+    pub fn close(&self) {
+        unsafe { notsafe::close_window(self.id.0) };
+    }
+
+    /// Keep the window open. Only has an effect when called from the event handler while it's
+    /// handling `CloseRequested`, not from a later event
+    pub fn veto_close(&self) {
+        unsafe { notsafe::veto_close(self.id.0) };
+    }
+
+    pub fn refresh(&self) {
+        unsafe { notsafe::refresh(self.id.0) };
+    }
+
+    pub fn set_title(&self, title: &str) {
+        let s = CString::new(title).unwrap();
+        unsafe { notsafe::set_title(self.id.0, s.as_ptr()) };
+    }
+
+    /// Resize the client area, in logical pixels
+    pub fn set_size(&self, width: u32, height: u32) {
+        unsafe { notsafe::set_size(self.id.0, width, height) };
+    }
+
+    /// Move the top left of the window, in the same units as `Moved`
+    pub fn set_position(&self, position: Point) {
+        unsafe { notsafe::set_position(self.id.0, position) };
+    }
+
+    /// Show or hide the window. A hidden window keeps its state and can be shown again
+    pub fn set_visible(&self, visible: bool) {
+        unsafe { notsafe::set_visible(self.id.0, visible) };
+    }
+
+    /// Cover the whole screen with the canvas, hiding the title bar, menu bar and status bar
+    pub fn set_fullscreen(&self, fullscreen: bool) {
+        unsafe { notsafe::set_fullscreen(self.id.0, fullscreen) };
+    }
+
+    pub fn maximize(&self) {
+        unsafe { notsafe::maximize(self.id.0) };
+    }
+
+    pub fn minimize(&self) {
+        unsafe { notsafe::minimize(self.id.0) };
+    }
+
+    /// Undo `maximize` or `minimize`. Fullscreen is left with `set_fullscreen(false)`
+    pub fn restore(&self) {
+        unsafe { notsafe::restore(self.id.0) };
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        unsafe { notsafe::is_fullscreen(self.id.0) }
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        unsafe { notsafe::is_maximized(self.id.0) }
+    }
+
+    pub fn is_minimized(&self) -> bool {
+        unsafe { notsafe::is_minimized(self.id.0) }
+    }
+
+    pub fn get_client_size(&self) -> Size {
+        unsafe { notsafe::get_client_size(self.id.0) }
+    }
+
+    pub fn get_display_size(&self) -> Size {
+        unsafe { notsafe::get_display_size(self.id.0) }
+    }
+
+    pub fn get_scale_factor(&self) -> f32 {
+        unsafe { notsafe::get_scale_factor(self.id.0) }
+    }
+
+    pub fn set_cursor(&self, cursor: CursorType) {
+        unsafe { notsafe::set_cursor(self.id.0, cursor) }
+    }
+
+    pub fn create_status_bar(&self) {
+        unsafe { notsafe::create_status_bar(self.id.0) }
+    }
+
+    pub fn set_status_text(&self, text: &str) {
+        let s = CString::new(text).unwrap();
+        unsafe { notsafe::set_status_text(self.id.0, s.as_ptr()) }
+    }
+
+    /// Show `menu_bar` in this window. A menu bar can only be shown in one window at a time, so
+    /// it's taken out of any other window it was in
+    pub fn set_menu_bar<T: Copy + std::fmt::Debug>(&self, menu_bar: &MenuBar<T>) {
+        set_menu_bar(self.id, menu_bar.ptr());
+    }
+
+    /// The icon shown in the window's title bar and taskbar entry, which is copied so `icon` can
+    /// be dropped afterwards. Windows don't have icons on macOS
+    pub fn set_icon(&self, icon: &Icon) {
+        unsafe { notsafe::set_icon(self.id.0, icon.ptr()) };
+    }
+
+    /// Show `menu` at the mouse, until an entry is chosen or it's dismissed
+    pub fn popup_menu<T: Copy + std::fmt::Debug>(&self, menu: &Menu<T>) {
+        set_status_menu(self.id, menu.ptr());
+    }
+
+    // Input methods
+
+    /// Tell the input method where the text being composed is, so it can place its candidate
+    /// window next to it rather than over it
+    pub fn set_ime_cursor_area(&self, area: Rect) {
+        unsafe { notsafe::set_ime_cursor_area(self.id.0, area) };
+    }
+
+    /// Whether the app handles input method composition itself. When allowed, the text being
+    /// composed is sent as `Composition*` events for the app to draw, and the keys that went into
+    /// it aren't sent. When disallowed, the input method is turned off and keys are sent as they
+    /// are.
+    ///
+    /// Until this is called, the platform draws the composition itself and sends the result as
+    /// `TextInput`. Composition events are not yet supported on macOS.
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        unsafe { notsafe::set_ime_allowed(self.id.0, allowed) };
+    }
+
+    // Mouse
+
+    /// Keep sending mouse events to the window while a button is held outside of it, until
+    /// `release_mouse`. The window is sent `MouseCaptureLost` if something else takes the mouse
+    pub fn capture_mouse(&self) {
+        unsafe { notsafe::capture_mouse(self.id.0) };
+    }
+
+    pub fn release_mouse(&self) {
+        unsafe { notsafe::release_mouse(self.id.0) };
+    }
+
+    /// Hide the cursor and keep it in the middle of the window, sending `RelativeMotion` in place
+    /// of `MouseMotion`. For camera controls that shouldn't stop at the edge of the screen.
+    /// Turned off again if the mouse capture is lost
+    pub fn set_relative_mouse_mode(&self, enabled: bool) {
+        unsafe { notsafe::set_relative_mouse_mode(self.id.0, enabled) };
+    }
+
+    /// Where the mouse is, relative to the top left of the canvas and in the same units as the
+    /// position of mouse events. Outside of the window, it can be negative or beyond the client
+    /// size
+    pub fn pointer_position(&self) -> Point {
+        unsafe { notsafe::get_pointer_position(self.id.0) }
+    }
+
+    /// Move the mouse to `position` on the canvas
+    pub fn warp_pointer(&self, position: Point) {
+        unsafe { notsafe::warp_pointer(self.id.0, position) };
     }
 }
 
+#[cfg(target_os = "linux")]
+const WAYLAND_UNSUPPORTED: &str = "wx-rs: Raw window handles aren't supported on Wayland. \
+    Set GDK_BACKEND=x11 to run under XWayland instead";
+
 unsafe impl HasRawWindowHandle for Window {
     #[cfg(windows)]
     fn raw_window_handle(&self) -> RawWindowHandle {
         unsafe {
-            let h = notsafe::get_windows_raw_window_handle();
+            let h = notsafe::get_windows_raw_window_handle(self.id.0);
             let mut handle = raw_window_handle::Win32WindowHandle::empty();
             handle.hwnd = h.hwnd as *mut _;
             handle.hinstance = h.hinstance as *mut _;
@@ -501,7 +1267,7 @@ unsafe impl HasRawWindowHandle for Window {
     #[cfg(target_os = "macos")]
     fn raw_window_handle(&self) -> RawWindowHandle {
         unsafe {
-            let h = notsafe::get_osx_raw_window_handle();
+            let h = notsafe::get_osx_raw_window_handle(self.id.0);
             let mut handle = raw_window_handle::AppKitWindowHandle::empty();
             handle.ns_window = h.ns_window as *mut _;
             handle.ns_view = h.ns_view as *mut _;
@@ -510,7 +1276,22 @@ unsafe impl HasRawWindowHandle for Window {
         }
     }
 
-    #[cfg(not(any(windows, target_os = "macos")))]
+    /// Panics on Wayland, where GTK draws the whole window, decorations and all, into a single
+    /// surface that can't be shared
+    #[cfg(target_os = "linux")]
+    fn raw_window_handle(&self) -> RawWindowHandle {
+        unsafe {
+            if notsafe::is_wayland() {
+                panic!("{}", WAYLAND_UNSUPPORTED);
+            }
+            let h = notsafe::get_x11_raw_window_handle(self.id.0);
+            let mut handle = raw_window_handle::XlibWindowHandle::empty();
+            handle.window = h.window;
+            RawWindowHandle::Xlib(handle)
+        }
+    }
+
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     fn raw_window_handle(&self) -> RawWindowHandle {
         panic!("Not supported")
     }
@@ -529,7 +1310,22 @@ unsafe impl HasRawDisplayHandle for Window {
         RawDisplayHandle::AppKit(handle)
     }
 
-    #[cfg(not(any(windows, target_os = "macos")))]
+    /// Panics on Wayland, like `raw_window_handle`
+    #[cfg(target_os = "linux")]
+    fn raw_display_handle(&self) -> RawDisplayHandle {
+        unsafe {
+            if notsafe::is_wayland() {
+                panic!("{}", WAYLAND_UNSUPPORTED);
+            }
+            let h = notsafe::get_x11_raw_window_handle(self.id.0);
+            let mut handle = raw_window_handle::XlibDisplayHandle::empty();
+            handle.display = h.display;
+            handle.screen = h.screen;
+            RawDisplayHandle::Xlib(handle)
+        }
+    }
+
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     fn raw_display_handle(&self) -> RawDisplayHandle {
         panic!("Not supported")
     }
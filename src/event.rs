@@ -0,0 +1,252 @@
+use std::os::raw::c_void;
+
+use super::bridge::*;
+use super::key::{KeyCode, ScanCode};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    Aux1,
+    Aux2,
+}
+
+/// Where a touch or trackpad gesture is, and whether this event is its first or last.
+/// A gesture made of a single event, like a tap, can be both
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Gesture {
+    pub position: Point,
+    pub start: bool,
+    pub end: bool,
+}
+
+/// An event sent to the handler given to `on_event`
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    MouseMotion {
+        position: Point,
+        modifiers: Modifiers,
+    },
+    MouseDown {
+        button: MouseButton,
+        position: Point,
+        modifiers: Modifiers,
+    },
+    MouseUp {
+        button: MouseButton,
+        position: Point,
+        modifiers: Modifiers,
+    },
+    MouseDoubleClick {
+        button: MouseButton,
+        position: Point,
+        modifiers: Modifiers,
+    },
+    /// `rotation / delta` is how many notches the wheel turned, each of which should scroll
+    /// `lines_per_action` lines or `columns_per_action` columns
+    Wheel {
+        axis: WheelAxis,
+        rotation: i32,
+        delta: i32,
+        lines_per_action: i32,
+        columns_per_action: i32,
+        /// The user has natural scrolling turned on, so the content follows their fingers
+        inverted: bool,
+        /// The scroll came from a trackpad or another smooth source rather than a notched
+        /// wheel. On macOS, `rotation` is then in pixels. On Windows and GTK it's still in
+        /// units of `delta`, and this is only guessed, from `rotation` not being a whole
+        /// number of notches
+        precise: bool,
+        position: Point,
+        modifiers: Modifiers,
+    },
+    /// Pinching on a trackpad. The change in magnification, where 0 is none
+    Magnify(f32),
+    MouseEnter,
+    MouseLeave,
+    /// Dragging with one or more fingers. `delta` is the distance moved since the last event
+    Pan {
+        gesture: Gesture,
+        delta: Point,
+    },
+    /// Pinching. `factor` is the zoom since the start of the gesture, starting at 1
+    Zoom {
+        gesture: Gesture,
+        factor: f32,
+    },
+    /// Twisting with two fingers. `angle` is the rotation since the start of the gesture, in
+    /// radians clockwise
+    Rotate {
+        gesture: Gesture,
+        angle: f32,
+    },
+    TwoFingerTap(Gesture),
+    LongPress(Gesture),
+    /// Holding one finger down and tapping with another
+    PressAndTap(Gesture),
+    /// The mouse was captured by something else while `capture_mouse` was in effect
+    MouseCaptureLost,
+    /// How far the mouse moved in relative mouse mode, unscaled. See `set_relative_mouse_mode`
+    RelativeMotion(Point),
+    KeyDown {
+        code: KeyCode,
+        scan_code: ScanCode,
+        /// The platform's own code for the key: a virtual key code on Windows and macOS, and a
+        /// keysym on GTK
+        raw_code: u32,
+        /// Whether this was sent because the key is being held down
+        is_repeat: bool,
+        modifiers: Modifiers,
+    },
+    KeyUp {
+        code: KeyCode,
+        scan_code: ScanCode,
+        raw_code: u32,
+        modifiers: Modifiers,
+    },
+    /// Text typed by the user, as produced by their keyboard layout. Sent after the `KeyDown`
+    /// that produced it, and includes dead-key compositions
+    TextInput(String),
+    /// An input method started composing text. See `set_ime_allowed`
+    CompositionStart,
+    /// The text being composed changed. `cursor` is a byte offset into `text`, if the input
+    /// method shows one
+    CompositionUpdate {
+        text: String,
+        cursor: Option<usize>,
+    },
+    /// The composition finished with this text. It's empty when the composition was cancelled
+    CompositionCommit(String),
+    /// The new client size of the window
+    Resized(Size),
+    Moved(Point),
+    /// The window was minimized, or brought back when false
+    Minimized(bool),
+    /// The window was maximized. Being restored from that is only sent as `Resized`, see
+    /// `Window::is_maximized`
+    Maximized,
+    /// The window went fullscreen, or came back out when false
+    FullScreen(bool),
+    Focus(bool),
+    Timer,
+    /// The user asked to close the window. It closes after the handler returns, unless the
+    /// handler calls `Window::veto_close`, e.g. to ask about unsaved changes first. A close asked
+    /// for while the handler is running, e.g. while it shows a modal dialog, is held off and
+    /// sent once it returns
+    CloseRequested,
+    /// The window is closing, and it can't be stopped. Sent after a `CloseRequested` that wasn't
+    /// vetoed, and on its own when the window is closed by `Window::close`, by `close_app`, by
+    /// its main window closing or by the session ending. When those are called from the handler,
+    /// `Exit` is sent once it returns
+    Exit,
+    /// The event id of the menu entry that was chosen. See `MenuBar::get_entry_from_event_id`
+    Menu(i32),
+    Unknown,
+}
+
+impl Event {
+    /// Safety: `event` must be the `wxEvent` that the bridge passed to the event handler, along
+    /// with `window`
+    pub(crate) unsafe fn from_raw(window: WindowId, event: *const c_void) -> Self {
+        use EventType::*;
+        let event_type = get_event_type(event);
+        let button = match event_type {
+            MouseLeftDown | MouseLeftUp | MouseLeftDclick => MouseButton::Left,
+            MouseMiddleDown | MouseMiddleUp | MouseMiddleDclick => MouseButton::Middle,
+            MouseRightDown | MouseRightUp | MouseRightDclick => MouseButton::Right,
+            MouseAux1Down | MouseAux1Up | MouseAux1Dclick => MouseButton::Aux1,
+            _ => MouseButton::Aux2,
+        };
+        match event_type {
+            MouseMotion => Event::MouseMotion {
+                position: get_mouse_position(event),
+                modifiers: get_mouse_modifiers(event),
+            },
+            MouseLeftDown | MouseMiddleDown | MouseRightDown | MouseAux1Down | MouseAux2Down => {
+                Event::MouseDown {
+                    button,
+                    position: get_mouse_position(event),
+                    modifiers: get_mouse_modifiers(event),
+                }
+            }
+            MouseLeftUp | MouseMiddleUp | MouseRightUp | MouseAux1Up | MouseAux2Up => {
+                Event::MouseUp {
+                    button,
+                    position: get_mouse_position(event),
+                    modifiers: get_mouse_modifiers(event),
+                }
+            }
+            MouseLeftDclick | MouseMiddleDclick | MouseRightDclick | MouseAux1Dclick
+            | MouseAux2Dclick => Event::MouseDoubleClick {
+                button,
+                position: get_mouse_position(event),
+                modifiers: get_mouse_modifiers(event),
+            },
+            MouseWheel => Event::Wheel {
+                axis: get_mouse_wheel_axis(event),
+                rotation: get_mouse_wheel_rotation(event),
+                delta: get_mouse_wheel_delta(event),
+                lines_per_action: get_mouse_wheel_lines_per_action(event),
+                columns_per_action: get_mouse_wheel_columns_per_action(event),
+                inverted: is_mouse_wheel_inverted(event),
+                precise: is_mouse_wheel_precise(event),
+                position: get_mouse_position(event),
+                modifiers: get_mouse_modifiers(event),
+            },
+            Magnify => Event::Magnify(get_magnification(event)),
+            MouseEnterWindow => Event::MouseEnter,
+            MouseLeaveWindow => Event::MouseLeave,
+            GesturePan => Event::Pan {
+                gesture: get_gesture(event),
+                delta: get_pan_delta(event),
+            },
+            GestureZoom => Event::Zoom {
+                gesture: get_gesture(event),
+                factor: get_zoom_factor(event),
+            },
+            GestureRotate => Event::Rotate {
+                gesture: get_gesture(event),
+                angle: get_rotation_angle(event),
+            },
+            TwoFingerTap => Event::TwoFingerTap(get_gesture(event)),
+            LongPress => Event::LongPress(get_gesture(event)),
+            PressAndTap => Event::PressAndTap(get_gesture(event)),
+            MouseCaptureLost => Event::MouseCaptureLost,
+            RelativeMotion => Event::RelativeMotion(get_relative_motion(event)),
+            KeyDown => Event::KeyDown {
+                code: get_event_key(event),
+                scan_code: get_scan_code(event),
+                raw_code: get_raw_key_code(event),
+                is_repeat: is_auto_repeat(event),
+                modifiers: get_modifiers(event),
+            },
+            KeyUp => Event::KeyUp {
+                code: get_event_key(event),
+                scan_code: get_scan_code(event),
+                raw_code: get_raw_key_code(event),
+                modifiers: get_modifiers(event),
+            },
+            Resize => Event::Resized(Window::from_id(window).get_client_size()),
+            WindowMove => Event::Moved(get_move_position(event)),
+            Iconize => Event::Minimized(get_event_iconized(event)),
+            Maximize => Event::Maximized,
+            FullScreen => Event::FullScreen(get_event_fullscreen(event)),
+            Focus => Event::Focus(get_event_focused(event)),
+            Timer => Event::Timer,
+            Exit => Event::Exit,
+            CloseRequested => Event::CloseRequested,
+            Menu => Event::Menu(get_event_id(event)),
+            TextInput => get_event_text(event).map_or(Event::Unknown, Event::TextInput),
+            CompositionStart => Event::CompositionStart,
+            CompositionUpdate => Event::CompositionUpdate {
+                text: get_ime_text(event),
+                cursor: get_ime_cursor(event),
+            },
+            CompositionCommit => Event::CompositionCommit(get_ime_text(event)),
+            ImeTextInput => Event::TextInput(get_ime_text(event)),
+            Unknown => Event::Unknown,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::os::raw::c_void;
+
+use super::bridge::*;
+
+/// A window icon, made of one or more images of different sizes. The platform picks whichever
+/// size suits where it's showing the icon. See `Window::set_icon`
+#[derive(Debug)]
+pub struct Icon {
+    ptr: *const c_void,
+}
+
+impl Default for Icon {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Icon {
+    /// An icon without any images yet
+    pub fn new() -> Self {
+        Self {
+            ptr: create_icon_bundle(),
+        }
+    }
+
+    /// An icon of a single image. See `add_rgba`
+    pub fn from_rgba(width: u32, height: u32, rgba: &[u8]) -> Self {
+        let mut icon = Self::new();
+        icon.add_rgba(width, height, rgba);
+        icon
+    }
+
+    /// An icon of the images in a PNG or ICO file, or `None` if it couldn't be read
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut icon = Self::new();
+        icon.add_bytes(bytes).then_some(icon)
+    }
+
+    /// Add an image of `width * height` pixels, given row by row as 4 bytes of red, green, blue
+    /// and alpha each. Panics if either size is 0, or if `rgba` is the wrong length
+    pub fn add_rgba(&mut self, width: u32, height: u32, rgba: &[u8]) {
+        add_rgba_to_icon_bundle(self.ptr, width, height, rgba);
+    }
+
+    /// Add every image in a PNG or ICO file. Returns false if it couldn't be read
+    pub fn add_bytes(&mut self, bytes: &[u8]) -> bool {
+        add_file_to_icon_bundle(self.ptr, bytes)
+    }
+
+    /// The size of each of the images
+    pub fn sizes(&self) -> Vec<Size> {
+        get_icon_bundle_sizes(self.ptr)
+    }
+
+    pub(crate) fn ptr(&self) -> *const c_void {
+        self.ptr
+    }
+}
+
+impl Drop for Icon {
+    fn drop(&mut self) {
+        delete_icon_bundle(self.ptr)
+    }
+}
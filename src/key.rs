@@ -0,0 +1,430 @@
+/// Generates `KeyCode` along with its conversions to and from the raw `wxKeyCode`, so that
+/// each key is listed once
+macro_rules! key_codes {
+    ($($(#[doc = $doc:literal])* $name:ident = $raw:expr,)*) => {
+        /// A key, as `wxKeyEvent::GetKeyCode` reports it. Converts to and from the raw code
+        /// with `From`.
+        ///
+        /// Every `KeyCode` made from a raw code converts back to it. A `Char` made by hand
+        /// might not: one that's a control character, one of the named keys' codes such as
+        /// `' '`, or in `'\u{12C}'..='\u{1FF}'`, which wxWidgets uses for its own keys,
+        /// comes back as the named key or as `Other`.
+        #[non_exhaustive]
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub enum KeyCode {
+            $($(#[doc = $doc])* $name,)*
+            /// A key that produces a character. Letters are reported in upper case, whatever
+            /// the state of shift and caps lock. See `Event::TextInput` for the text typed
+            Char(char),
+            /// A raw code with no other variant
+            Other(i32),
+        }
+
+        impl From<i32> for KeyCode {
+            fn from(raw: i32) -> Self {
+                $(if raw == $raw {
+                    return KeyCode::$name;
+                })*
+                match u32::try_from(raw).ok().and_then(char::from_u32) {
+                    Some(c) if !c.is_control() && !WX_KEY_CODES.contains(&raw) => {
+                        KeyCode::Char(c)
+                    }
+                    _ => KeyCode::Other(raw),
+                }
+            }
+        }
+
+        /// Every named variant, to test the conversions with
+        #[cfg(test)]
+        const NAMED_KEY_CODES: &[KeyCode] = &[$(KeyCode::$name,)*];
+
+        impl From<KeyCode> for i32 {
+            fn from(code: KeyCode) -> Self {
+                match code {
+                    $(KeyCode::$name => $raw,)*
+                    KeyCode::Char(c) => c as i32,
+                    KeyCode::Other(raw) => raw,
+                }
+            }
+        }
+    };
+}
+
+// The codes wxWidgets numbers its own keys with, which aren't characters even when unused
+const WX_KEY_CODES: std::ops::Range<i32> = 300..512;
+const WXK_SPECIAL1: i32 = 397;
+const WXK_BROWSER_BACK: i32 = 417;
+
+key_codes! {
+    Back = 8,
+    Tab = 9,
+    Return = 13,
+    Escape = 27,
+    Space = 32,
+    Delete = 127,
+
+    Start = 300,
+    LButton = 301,
+    RButton = 302,
+    Cancel = 303,
+    MButton = 304,
+    Clear = 305,
+    Shift = 306,
+    Alt = 307,
+    /// Control, or Command on macOS
+    Control = 308,
+    Menu = 309,
+    Pause = 310,
+    Capital = 311,
+    End = 312,
+    Home = 313,
+    Left = 314,
+    Up = 315,
+    Right = 316,
+    Down = 317,
+    Select = 318,
+    Print = 319,
+    Execute = 320,
+    Snapshot = 321,
+    Insert = 322,
+    Help = 323,
+    Numpad0 = 324,
+    Numpad1 = 325,
+    Numpad2 = 326,
+    Numpad3 = 327,
+    Numpad4 = 328,
+    Numpad5 = 329,
+    Numpad6 = 330,
+    Numpad7 = 331,
+    Numpad8 = 332,
+    Numpad9 = 333,
+    Multiply = 334,
+    Add = 335,
+    Separator = 336,
+    Subtract = 337,
+    Decimal = 338,
+    Divide = 339,
+    F1 = 340,
+    F2 = 341,
+    F3 = 342,
+    F4 = 343,
+    F5 = 344,
+    F6 = 345,
+    F7 = 346,
+    F8 = 347,
+    F9 = 348,
+    F10 = 349,
+    F11 = 350,
+    F12 = 351,
+    F13 = 352,
+    F14 = 353,
+    F15 = 354,
+    F16 = 355,
+    F17 = 356,
+    F18 = 357,
+    F19 = 358,
+    F20 = 359,
+    F21 = 360,
+    F22 = 361,
+    F23 = 362,
+    F24 = 363,
+    NumLock = 364,
+    Scroll = 365,
+    PageUp = 366,
+    PageDown = 367,
+    NumpadSpace = 368,
+    NumpadTab = 369,
+    NumpadEnter = 370,
+    NumpadF1 = 371,
+    NumpadF2 = 372,
+    NumpadF3 = 373,
+    NumpadF4 = 374,
+    NumpadHome = 375,
+    NumpadLeft = 376,
+    NumpadUp = 377,
+    NumpadRight = 378,
+    NumpadDown = 379,
+    NumpadPageUp = 380,
+    NumpadPageDown = 381,
+    NumpadEnd = 382,
+    NumpadBegin = 383,
+    NumpadInsert = 384,
+    NumpadDelete = 385,
+    NumpadEqual = 386,
+    NumpadMultiply = 387,
+    NumpadAdd = 388,
+    NumpadSeparator = 389,
+    NumpadSubtract = 390,
+    NumpadDecimal = 391,
+    NumpadDivide = 392,
+    WindowsLeft = 393,
+    WindowsRight = 394,
+    WindowsMenu = 395,
+    /// The Control key itself, only sent on macOS where `Control` is Command
+    RawControl = 396,
+
+    // Hardware-specific buttons
+    Special1 = WXK_SPECIAL1,
+    Special2 = WXK_SPECIAL1 + 1,
+    Special3 = WXK_SPECIAL1 + 2,
+    Special4 = WXK_SPECIAL1 + 3,
+    Special5 = WXK_SPECIAL1 + 4,
+    Special6 = WXK_SPECIAL1 + 5,
+    Special7 = WXK_SPECIAL1 + 6,
+    Special8 = WXK_SPECIAL1 + 7,
+    Special9 = WXK_SPECIAL1 + 8,
+    Special10 = WXK_SPECIAL1 + 9,
+    Special11 = WXK_SPECIAL1 + 10,
+    Special12 = WXK_SPECIAL1 + 11,
+    Special13 = WXK_SPECIAL1 + 12,
+    Special14 = WXK_SPECIAL1 + 13,
+    Special15 = WXK_SPECIAL1 + 14,
+    Special16 = WXK_SPECIAL1 + 15,
+    Special17 = WXK_SPECIAL1 + 16,
+    Special18 = WXK_SPECIAL1 + 17,
+    Special19 = WXK_SPECIAL1 + 18,
+    Special20 = WXK_SPECIAL1 + 19,
+
+    // Media and browser keys
+    BrowserBack = WXK_BROWSER_BACK,
+    BrowserForward = WXK_BROWSER_BACK + 1,
+    BrowserRefresh = WXK_BROWSER_BACK + 2,
+    BrowserStop = WXK_BROWSER_BACK + 3,
+    BrowserSearch = WXK_BROWSER_BACK + 4,
+    BrowserFavorites = WXK_BROWSER_BACK + 5,
+    BrowserHome = WXK_BROWSER_BACK + 6,
+    VolumeMute = WXK_BROWSER_BACK + 7,
+    VolumeDown = WXK_BROWSER_BACK + 8,
+    VolumeUp = WXK_BROWSER_BACK + 9,
+    MediaNextTrack = WXK_BROWSER_BACK + 10,
+    MediaPrevTrack = WXK_BROWSER_BACK + 11,
+    MediaStop = WXK_BROWSER_BACK + 12,
+    MediaPlayPause = WXK_BROWSER_BACK + 13,
+    LaunchMail = WXK_BROWSER_BACK + 14,
+    LaunchApp1 = WXK_BROWSER_BACK + 15,
+    LaunchApp2 = WXK_BROWSER_BACK + 16,
+}
+
+/// Generates `ScanCode` along with the table it's looked up in. Each key is given as its USB HID
+/// usage, followed by the native code for it on Windows, Linux and macOS, or -1 if it has none
+macro_rules! scan_codes {
+    ($($(#[doc = $doc:literal])* $name:ident = $usage:expr, $windows:expr, $linux:expr, $macos:expr,)*) => {
+        /// A physical key, independent of the keyboard layout. Named after the key in that
+        /// position on a US keyboard, and numbered with its USB HID usage.
+        #[non_exhaustive]
+        #[repr(u16)]
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub enum ScanCode {
+            Unidentified = 0,
+            $($(#[doc = $doc])* $name = $usage,)*
+        }
+
+        /// `(key, Windows, Linux, macOS)`
+        const NATIVE_SCAN_CODES: &[(ScanCode, i32, i32, i32)] = &[
+            $((ScanCode::$name, $windows, $linux, $macos),)*
+        ];
+    };
+}
+
+impl ScanCode {
+    /// The key's usage on the USB HID keyboard page
+    pub fn usb_usage(self) -> u16 {
+        self as u16
+    }
+
+    /// Look up the code the bridge reports: a scan code with 0xE000 set for extended keys on
+    /// Windows, an evdev code on Linux and a virtual key code on macOS
+    pub(crate) fn from_native(native: u32) -> Self {
+        let native = native as i32;
+        NATIVE_SCAN_CODES
+            .iter()
+            .find(|(_, windows, linux, macos)| {
+                if cfg!(windows) {
+                    *windows == native
+                } else if cfg!(target_os = "macos") {
+                    *macos == native
+                } else {
+                    *linux == native
+                }
+            })
+            .map_or(ScanCode::Unidentified, |(key, ..)| *key)
+    }
+}
+
+scan_codes! {
+    KeyA = 0x04, 0x1E, 30, 0x00,
+    KeyB = 0x05, 0x30, 48, 0x0B,
+    KeyC = 0x06, 0x2E, 46, 0x08,
+    KeyD = 0x07, 0x20, 32, 0x02,
+    KeyE = 0x08, 0x12, 18, 0x0E,
+    KeyF = 0x09, 0x21, 33, 0x03,
+    KeyG = 0x0A, 0x22, 34, 0x05,
+    KeyH = 0x0B, 0x23, 35, 0x04,
+    KeyI = 0x0C, 0x17, 23, 0x22,
+    KeyJ = 0x0D, 0x24, 36, 0x26,
+    KeyK = 0x0E, 0x25, 37, 0x28,
+    KeyL = 0x0F, 0x26, 38, 0x25,
+    KeyM = 0x10, 0x32, 50, 0x2E,
+    KeyN = 0x11, 0x31, 49, 0x2D,
+    KeyO = 0x12, 0x18, 24, 0x1F,
+    KeyP = 0x13, 0x19, 25, 0x23,
+    KeyQ = 0x14, 0x10, 16, 0x0C,
+    KeyR = 0x15, 0x13, 19, 0x0F,
+    KeyS = 0x16, 0x1F, 31, 0x01,
+    KeyT = 0x17, 0x14, 20, 0x11,
+    KeyU = 0x18, 0x16, 22, 0x20,
+    KeyV = 0x19, 0x2F, 47, 0x09,
+    KeyW = 0x1A, 0x11, 17, 0x0D,
+    KeyX = 0x1B, 0x2D, 45, 0x07,
+    KeyY = 0x1C, 0x15, 21, 0x10,
+    KeyZ = 0x1D, 0x2C, 44, 0x06,
+    Digit1 = 0x1E, 0x02, 2, 0x12,
+    Digit2 = 0x1F, 0x03, 3, 0x13,
+    Digit3 = 0x20, 0x04, 4, 0x14,
+    Digit4 = 0x21, 0x05, 5, 0x15,
+    Digit5 = 0x22, 0x06, 6, 0x17,
+    Digit6 = 0x23, 0x07, 7, 0x16,
+    Digit7 = 0x24, 0x08, 8, 0x1A,
+    Digit8 = 0x25, 0x09, 9, 0x1C,
+    Digit9 = 0x26, 0x0A, 10, 0x19,
+    Digit0 = 0x27, 0x0B, 11, 0x1D,
+    Enter = 0x28, 0x1C, 28, 0x24,
+    Escape = 0x29, 0x01, 1, 0x35,
+    Backspace = 0x2A, 0x0E, 14, 0x33,
+    Tab = 0x2B, 0x0F, 15, 0x30,
+    Space = 0x2C, 0x39, 57, 0x31,
+    Minus = 0x2D, 0x0C, 12, 0x1B,
+    Equal = 0x2E, 0x0D, 13, 0x18,
+    BracketLeft = 0x2F, 0x1A, 26, 0x21,
+    BracketRight = 0x30, 0x1B, 27, 0x1E,
+    Backslash = 0x31, 0x2B, 43, 0x2A,
+    Semicolon = 0x33, 0x27, 39, 0x29,
+    Quote = 0x34, 0x28, 40, 0x27,
+    Backquote = 0x35, 0x29, 41, 0x32,
+    Comma = 0x36, 0x33, 51, 0x2B,
+    Period = 0x37, 0x34, 52, 0x2F,
+    Slash = 0x38, 0x35, 53, 0x2C,
+    CapsLock = 0x39, 0x3A, 58, 0x39,
+    F1 = 0x3A, 0x3B, 59, 0x7A,
+    F2 = 0x3B, 0x3C, 60, 0x78,
+    F3 = 0x3C, 0x3D, 61, 0x63,
+    F4 = 0x3D, 0x3E, 62, 0x76,
+    F5 = 0x3E, 0x3F, 63, 0x60,
+    F6 = 0x3F, 0x40, 64, 0x61,
+    F7 = 0x40, 0x41, 65, 0x62,
+    F8 = 0x41, 0x42, 66, 0x64,
+    F9 = 0x42, 0x43, 67, 0x65,
+    F10 = 0x43, 0x44, 68, 0x6D,
+    F11 = 0x44, 0x57, 87, 0x67,
+    F12 = 0x45, 0x58, 88, 0x6F,
+    PrintScreen = 0x46, 0xE037, 99, -1,
+    ScrollLock = 0x47, 0x46, 70, -1,
+    Pause = 0x48, 0x45, 119, -1,
+    /// Help on Apple keyboards
+    Insert = 0x49, 0xE052, 110, 0x72,
+    Home = 0x4A, 0xE047, 102, 0x73,
+    PageUp = 0x4B, 0xE049, 104, 0x74,
+    Delete = 0x4C, 0xE053, 111, 0x75,
+    End = 0x4D, 0xE04F, 107, 0x77,
+    PageDown = 0x4E, 0xE051, 109, 0x79,
+    ArrowRight = 0x4F, 0xE04D, 106, 0x7C,
+    ArrowLeft = 0x50, 0xE04B, 105, 0x7B,
+    ArrowDown = 0x51, 0xE050, 108, 0x7D,
+    ArrowUp = 0x52, 0xE048, 103, 0x7E,
+    /// Clear on Apple keyboards
+    NumLock = 0x53, 0xE045, 69, 0x47,
+    NumpadDivide = 0x54, 0xE035, 98, 0x4B,
+    NumpadMultiply = 0x55, 0x37, 55, 0x43,
+    NumpadSubtract = 0x56, 0x4A, 74, 0x4E,
+    NumpadAdd = 0x57, 0x4E, 78, 0x45,
+    NumpadEnter = 0x58, 0xE01C, 96, 0x4C,
+    Numpad1 = 0x59, 0x4F, 79, 0x53,
+    Numpad2 = 0x5A, 0x50, 80, 0x54,
+    Numpad3 = 0x5B, 0x51, 81, 0x55,
+    Numpad4 = 0x5C, 0x4B, 75, 0x56,
+    Numpad5 = 0x5D, 0x4C, 76, 0x57,
+    Numpad6 = 0x5E, 0x4D, 77, 0x58,
+    Numpad7 = 0x5F, 0x47, 71, 0x59,
+    Numpad8 = 0x60, 0x48, 72, 0x5B,
+    Numpad9 = 0x61, 0x49, 73, 0x5C,
+    Numpad0 = 0x62, 0x52, 82, 0x52,
+    NumpadDecimal = 0x63, 0x53, 83, 0x41,
+    /// The key next to left shift on ISO keyboards
+    IntlBackslash = 0x64, 0x56, 86, 0x0A,
+    ContextMenu = 0x65, 0xE05D, 127, 0x6E,
+    Power = 0x66, 0xE05E, 116, -1,
+    NumpadEqual = 0x67, 0x59, 117, 0x51,
+    F13 = 0x68, 0x64, 183, 0x69,
+    F14 = 0x69, 0x65, 184, 0x6B,
+    F15 = 0x6A, 0x66, 185, 0x71,
+    F16 = 0x6B, 0x67, 186, 0x6A,
+    F17 = 0x6C, 0x68, 187, 0x40,
+    F18 = 0x6D, 0x69, 188, 0x4F,
+    F19 = 0x6E, 0x6A, 189, 0x50,
+    F20 = 0x6F, 0x6B, 190, 0x5A,
+    F21 = 0x70, 0x6C, 191, -1,
+    F22 = 0x71, 0x6D, 192, -1,
+    F23 = 0x72, 0x6E, 193, -1,
+    F24 = 0x73, 0x76, 194, -1,
+    Help = 0x75, -1, 138, -1,
+    AudioVolumeMute = 0x7F, 0xE020, 113, 0x4A,
+    AudioVolumeUp = 0x80, 0xE030, 115, 0x48,
+    AudioVolumeDown = 0x81, 0xE02E, 114, 0x49,
+    NumpadComma = 0x85, 0x7E, 121, 0x5F,
+    IntlRo = 0x87, 0x73, 89, 0x5E,
+    KanaMode = 0x88, 0x70, 93, -1,
+    IntlYen = 0x89, 0x7D, 124, 0x5D,
+    Convert = 0x8A, 0x79, 92, -1,
+    NonConvert = 0x8B, 0x7B, 94, -1,
+    Lang1 = 0x90, 0x72, 122, 0x68,
+    Lang2 = 0x91, 0x71, 123, 0x66,
+    ControlLeft = 0xE0, 0x1D, 29, 0x3B,
+    ShiftLeft = 0xE1, 0x2A, 42, 0x38,
+    AltLeft = 0xE2, 0x38, 56, 0x3A,
+    MetaLeft = 0xE3, 0xE05B, 125, 0x37,
+    ControlRight = 0xE4, 0xE01D, 97, 0x3E,
+    ShiftRight = 0xE5, 0x36, 54, 0x3C,
+    AltRight = 0xE6, 0xE038, 100, 0x3D,
+    MetaRight = 0xE7, 0xE05C, 126, 0x36,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_keys_round_trip() {
+        for &key in NAMED_KEY_CODES {
+            assert_eq!(KeyCode::from(i32::from(key)), key);
+        }
+    }
+
+    #[test]
+    fn chars_round_trip() {
+        for c in ['A', '0', '!', '\u{12B}', '\u{200}', 'é', '€'] {
+            assert_eq!(KeyCode::from(i32::from(KeyCode::Char(c))), KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn other_codes_round_trip() {
+        for raw in [-1, 0, 1, 31, 511, 0xD800, 0x110000] {
+            let key = KeyCode::from(raw);
+            assert_eq!(key, KeyCode::Other(raw));
+            assert_eq!(i32::from(key), raw);
+        }
+    }
+
+    #[test]
+    fn chars_used_by_named_keys_dont_round_trip() {
+        assert_eq!(KeyCode::from(i32::from(KeyCode::Char(' '))), KeyCode::Space);
+        assert_eq!(
+            KeyCode::from(i32::from(KeyCode::Char('\u{12C}'))),
+            KeyCode::Start
+        );
+        assert_eq!(
+            KeyCode::from(i32::from(KeyCode::Char('\u{1FF}'))),
+            KeyCode::Other(511)
+        );
+    }
+}
@@ -1,5 +1,17 @@
 mod bridge;
 pub use bridge::*;
 
+mod event;
+pub use event::*;
+
+mod icon;
+pub use icon::*;
+
+mod key;
+pub use key::*;
+
 mod menu;
 pub use menu::*;
+
+#[cfg(feature = "mock")]
+pub mod mock;
@@ -2,7 +2,7 @@ use std::os::raw::c_void;
 
 use super::bridge::*;
 
-/// This should only ever be created once
+/// Shown in a window with `Window::set_menu_bar`
 #[derive(Debug)]
 pub struct MenuBar<T: Copy + std::fmt::Debug> {
     ptr: *const c_void,
@@ -53,11 +53,9 @@ impl<T: PartialEq + Copy + std::fmt::Debug> Menu<T> {
     pub fn enable_item_by_id(&mut self, id: T) {
         for (i, item) in self.items.iter_mut().enumerate() {
             match item {
-                MenuItem::Entry(e) => {
-                    if e.id == id {
-                        self.enable_item(i);
-                        return;
-                    }
+                MenuItem::Entry(e) if e.id == id => {
+                    self.enable_item(i);
+                    return;
                 }
                 MenuItem::Submenu(s) => s.enable_item_by_id(id),
                 _ => (),
@@ -68,11 +66,9 @@ impl<T: PartialEq + Copy + std::fmt::Debug> Menu<T> {
     pub fn disable_item_by_id(&mut self, id: T) {
         for (i, item) in self.items.iter_mut().enumerate() {
             match item {
-                MenuItem::Entry(e) => {
-                    if e.id == id {
-                        self.disable_item(i);
-                        return;
-                    }
+                MenuItem::Entry(e) if e.id == id => {
+                    self.disable_item(i);
+                    return;
                 }
                 MenuItem::Submenu(s) => s.disable_item_by_id(id),
                 _ => (),
@@ -114,10 +110,8 @@ impl<T: Copy + std::fmt::Debug> Menu<T> {
     pub fn get_entry_from_event_id(&self, event_id: i32) -> Option<T> {
         for item in self.items.iter() {
             match item {
-                MenuItem::Entry(e) => {
-                    if e.event_id == event_id {
-                        return Some(e.id);
-                    }
+                MenuItem::Entry(e) if e.event_id == event_id => {
+                    return Some(e.id);
                 }
                 MenuItem::Submenu(s) => {
                     let r = s.get_entry_from_event_id(event_id);
@@ -149,15 +143,10 @@ impl<T: Copy + std::fmt::Debug> Menu<T> {
                     .as_ref()
                     .expect("Submenus must have names")
                     .as_str(),
-                sub.help.as_ref().map(|s| s.as_str()),
+                sub.help.as_deref(),
             ),
             MenuItem::Entry(entry) => {
-                let id = insert_to_menu(
-                    self.ptr,
-                    i,
-                    &entry.name,
-                    entry.help.as_ref().map(|s| s.as_str()),
-                );
+                let id = insert_to_menu(self.ptr, i, &entry.name, entry.help.as_deref());
                 entry.event_id = id;
             }
             MenuItem::Separator => insert_separator_to_menu(self.ptr, i),
@@ -170,8 +159,16 @@ impl<T: Copy + std::fmt::Debug> Menu<T> {
         self.items.remove(i);
     }
 
-    pub fn popup(&self) {
-        set_status_menu(self.ptr);
+    pub(crate) fn ptr(&self) -> *const c_void {
+        self.ptr
+    }
+}
+
+#[cfg(feature = "mock")]
+impl<T: Copy + std::fmt::Debug> Menu<T> {
+    /// What the mock backend has recorded for this menu
+    pub fn mock_state(&self) -> crate::mock::MenuState {
+        crate::mock::menu_state(self.ptr)
     }
 }
 
@@ -181,6 +178,12 @@ impl<T: Copy + std::fmt::Debug> Drop for Menu<T> {
     }
 }
 
+impl<T: Copy + std::fmt::Debug> Default for MenuBar<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: Copy + std::fmt::Debug> MenuBar<T> {
     pub fn new() -> Self {
         Self {
@@ -189,8 +192,8 @@ impl<T: Copy + std::fmt::Debug> MenuBar<T> {
         }
     }
 
-    pub fn show(&self) {
-        set_menu_bar(self.ptr)
+    pub(crate) fn ptr(&self) -> *const c_void {
+        self.ptr
     }
 
     pub fn append(&mut self, menu: Menu<T>) {
@@ -236,6 +239,14 @@ impl<T: Copy + std::fmt::Debug> MenuBar<T> {
     }
 }
 
+#[cfg(feature = "mock")]
+impl<T: Copy + std::fmt::Debug> MenuBar<T> {
+    /// What the mock backend has recorded for this menu bar
+    pub fn mock_state(&self) -> crate::mock::MenuBarState {
+        crate::mock::menu_bar_state(self.ptr)
+    }
+}
+
 impl<T: Copy + std::fmt::Debug> Drop for MenuBar<T> {
     fn drop(&mut self) {
         self.clear();
@@ -0,0 +1,1599 @@
+//! A headless backend that records the calls made to the bridge, in place of wxWidgets.
+//!
+//! Enabled with the `mock` feature. State is kept per thread, so each test gets its own
+//! windows, menus and clipboard.
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::os::raw::{c_int, c_void};
+
+use super::bridge::notsafe::WindowOptions;
+use super::bridge::{
+    dispatch_event, dispatch_render, reset_callbacks, CursorType, Data, Modifiers, Point, Rect,
+    Size, WindowBuilder, WindowId,
+};
+use super::event::Event;
+
+/// A call to the bridge that changed its state
+#[derive(Debug, Clone, PartialEq)]
+pub enum Call {
+    /// With the main window's builder
+    InitApp(WindowBuilder),
+    SetRender,
+    RunApp,
+    CloseApp,
+    CreateWindow {
+        window: WindowId,
+        builder: WindowBuilder,
+    },
+    CloseWindow(WindowId),
+    VetoClose(WindowId),
+    RequestClose(WindowId),
+    SetTitle {
+        window: WindowId,
+        title: String,
+    },
+    SetSize {
+        window: WindowId,
+        width: u32,
+        height: u32,
+    },
+    SetPosition {
+        window: WindowId,
+        position: Point,
+    },
+    SetVisible {
+        window: WindowId,
+        visible: bool,
+    },
+    SetFullscreen {
+        window: WindowId,
+        fullscreen: bool,
+    },
+    Maximize(WindowId),
+    Minimize(WindowId),
+    Restore(WindowId),
+    Refresh(WindowId),
+    BindCanvasEvents,
+    SetCursor {
+        window: WindowId,
+        cursor: CursorType,
+    },
+    CaptureMouse(WindowId),
+    ReleaseMouse(WindowId),
+    SetRelativeMouseMode {
+        window: WindowId,
+        enabled: bool,
+    },
+    WarpPointer {
+        window: WindowId,
+        position: Point,
+    },
+    SetImeCursorArea {
+        window: WindowId,
+        area: Rect,
+    },
+    SetImeAllowed {
+        window: WindowId,
+        allowed: bool,
+    },
+    CreateStatusBar(WindowId),
+    SetStatusText {
+        window: WindowId,
+        text: String,
+    },
+    PutOnClipboard(Data),
+    CreateMenu(usize),
+    InsertToMenu {
+        menu: usize,
+        i: usize,
+        entry: String,
+        help: Option<String>,
+    },
+    InsertSubmenu {
+        menu: usize,
+        i: usize,
+        submenu: usize,
+        entry: String,
+        help: Option<String>,
+    },
+    InsertSeparatorToMenu {
+        menu: usize,
+        i: usize,
+    },
+    RemoveFromMenu {
+        menu: usize,
+        i: usize,
+    },
+    EnableMenuItem {
+        menu: usize,
+        i: usize,
+        enable: bool,
+    },
+    PopupMenu {
+        window: WindowId,
+        menu: usize,
+    },
+    DeleteMenu(usize),
+    CreateMenuBar(usize),
+    InsertToMenuBar {
+        menu_bar: usize,
+        menu: usize,
+        i: usize,
+        entry: String,
+    },
+    RemoveFromMenuBar {
+        menu_bar: usize,
+        i: usize,
+    },
+    SetMenuBar {
+        window: WindowId,
+        menu_bar: usize,
+    },
+    DeleteMenuBar(usize),
+    CreateIcon(usize),
+    AddRgbaToIcon {
+        icon: usize,
+        width: u32,
+        height: u32,
+    },
+    AddFileToIcon(usize),
+    DeleteIcon(usize),
+    SetIcon {
+        window: WindowId,
+        icon: usize,
+    },
+}
+
+/// The contents of a menu, as the mock backend sees it
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuState {
+    pub items: Vec<MenuItemState>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MenuItemState {
+    Entry {
+        event_id: i32,
+        label: String,
+        help: Option<String>,
+        enabled: bool,
+    },
+    Submenu {
+        label: String,
+        help: Option<String>,
+        menu: MenuState,
+    },
+    Separator,
+}
+
+/// The contents of a menu bar, as `(label, menu)` pairs
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuBarState {
+    pub menus: Vec<(String, MenuState)>,
+}
+
+#[derive(Debug, Clone)]
+enum Item {
+    Entry {
+        event_id: i32,
+        label: String,
+        help: Option<String>,
+        enabled: bool,
+    },
+    Submenu {
+        submenu: usize,
+        label: String,
+        help: Option<String>,
+    },
+    Separator,
+}
+
+#[derive(Default)]
+struct WindowState {
+    title: String,
+    position: Option<Point>,
+    visible: bool,
+    fullscreen: bool,
+    maximized: bool,
+    minimized: bool,
+    close_vetoed: bool,
+    client_size: (u32, u32),
+    cursor: Option<CursorType>,
+    ime_cursor_area: Option<Rect>,
+    ime_allowed: Option<bool>,
+    status_bar: bool,
+    status_text: Option<String>,
+    mouse_captured: bool,
+    relative_mouse_mode: bool,
+    pointer_position: Option<Point>,
+    menu_bar: Option<usize>,
+    popup_menu: Option<usize>,
+    icon: Option<Vec<Size>>,
+}
+
+#[derive(Default)]
+struct State {
+    calls: Vec<Call>,
+    windows: BTreeMap<c_int, WindowState>,
+    main_window: c_int,
+    next_window: c_int,
+    keyboard_state: Option<Modifiers>,
+    clipboard: Option<Data>,
+    next_handle: usize,
+    next_event_id: i32,
+    menus: HashMap<usize, Vec<Item>>,
+    menu_bars: HashMap<usize, Vec<(usize, String)>>,
+    icons: HashMap<usize, Vec<Size>>,
+}
+
+impl State {
+    fn new_handle(&mut self) -> usize {
+        self.next_handle += 1;
+        self.next_handle
+    }
+
+    fn create_window(&mut self, title: String, options: &WindowOptions) -> c_int {
+        self.next_window += 1;
+        let window = WindowState {
+            title,
+            position: options.has_position.then_some(Point {
+                x: options.x,
+                y: options.y,
+            }),
+            visible: options.visible,
+            client_size: (options.width, options.height),
+            ..WindowState::default()
+        };
+        self.windows.insert(self.next_window, window);
+        self.next_window
+    }
+
+    /// Closing the main window closes the rest, as with wxWidgets
+    fn close_window(&mut self, window: c_int) {
+        if window == self.main_window {
+            self.windows.clear();
+            self.main_window = 0;
+        } else {
+            self.windows.remove(&window);
+        }
+    }
+
+    fn window(&mut self, window: c_int) -> Option<&mut WindowState> {
+        self.windows.get_mut(&window)
+    }
+
+    fn menu_state(&self, menu: usize) -> MenuState {
+        let items = self.menus.get(&menu).map_or(vec![], |items| {
+            items
+                .iter()
+                .map(|item| match item {
+                    Item::Entry {
+                        event_id,
+                        label,
+                        help,
+                        enabled,
+                    } => MenuItemState::Entry {
+                        event_id: *event_id,
+                        label: label.clone(),
+                        help: help.clone(),
+                        enabled: *enabled,
+                    },
+                    Item::Submenu {
+                        submenu,
+                        label,
+                        help,
+                    } => MenuItemState::Submenu {
+                        label: label.clone(),
+                        help: help.clone(),
+                        menu: self.menu_state(*submenu),
+                    },
+                    Item::Separator => MenuItemState::Separator,
+                })
+                .collect()
+        });
+        MenuState { items }
+    }
+
+    fn menu_bar_state(&self, menu_bar: usize) -> MenuBarState {
+        let menus = self.menu_bars.get(&menu_bar).map_or(vec![], |menus| {
+            menus
+                .iter()
+                .map(|(menu, label)| (label.clone(), self.menu_state(*menu)))
+                .collect()
+        });
+        MenuBarState { menus }
+    }
+}
+
+thread_local!(
+    static STATE: RefCell<State> = RefCell::new(State::default());
+);
+
+fn with_state<R>(f: impl FnOnce(&mut State) -> R) -> R {
+    STATE.with(|state| f(&mut state.borrow_mut()))
+}
+
+/// Every state-changing call made to the bridge on this thread, in order
+pub fn calls() -> Vec<Call> {
+    with_state(|state| state.calls.clone())
+}
+
+/// Forget the calls recorded so far, leaving the rest of the state alone
+pub fn clear_calls() {
+    with_state(|state| state.calls.clear())
+}
+
+/// Throw away all of the mock state on this thread, along with the callbacks given to
+/// `set_render` and `on_event`
+pub fn reset() {
+    with_state(|state| *state = State::default());
+    reset_callbacks();
+}
+
+/// The windows that are open, starting with the main one
+pub fn windows() -> Vec<WindowId> {
+    with_state(|state| state.windows.keys().map(|w| WindowId(*w)).collect())
+}
+
+/// Read the state of `window`, or `None` if it isn't open
+fn read_window<R>(window: WindowId, f: impl FnOnce(&WindowState) -> R) -> Option<R> {
+    with_state(|state| state.windows.get(&window.0).map(f))
+}
+
+/// The title of `window`, if it's open
+pub fn title(window: WindowId) -> Option<String> {
+    read_window(window, |w| w.title.clone())
+}
+
+/// Where `window` was last placed, if it was given a position
+pub fn position(window: WindowId) -> Option<Point> {
+    read_window(window, |w| w.position).flatten()
+}
+
+/// Whether `window` is open and shown
+pub fn is_visible(window: WindowId) -> bool {
+    read_window(window, |w| w.visible).unwrap_or(false)
+}
+
+/// The text in the status bar of `window`, if one has been created and given text
+pub fn status_text(window: WindowId) -> Option<String> {
+    read_window(window, |w| w.status_text.clone()).flatten()
+}
+
+/// The last cursor that was set on `window`
+pub fn cursor(window: WindowId) -> Option<CursorType> {
+    read_window(window, |w| w.cursor).flatten()
+}
+
+/// Whether the mouse is captured by `window`, through `capture_mouse` or relative mouse mode
+pub fn mouse_captured(window: WindowId) -> bool {
+    read_window(window, |w| w.mouse_captured || w.relative_mouse_mode).unwrap_or(false)
+}
+
+pub fn relative_mouse_mode(window: WindowId) -> bool {
+    read_window(window, |w| w.relative_mouse_mode).unwrap_or(false)
+}
+
+/// Move the simulated mouse over `window`, as `warp_pointer` does but without recording a call
+pub fn set_pointer_position(window: WindowId, position: Point) {
+    with_state(|state| {
+        if let Some(w) = state.window(window.0) {
+            w.pointer_position = Some(position);
+        }
+    })
+}
+
+/// The last area given to `set_ime_cursor_area` for `window`
+pub fn ime_cursor_area(window: WindowId) -> Option<Rect> {
+    read_window(window, |w| w.ime_cursor_area).flatten()
+}
+
+/// The last value given to `set_ime_allowed` for `window`, if it has been called
+pub fn ime_allowed(window: WindowId) -> Option<bool> {
+    read_window(window, |w| w.ime_allowed).flatten()
+}
+
+/// Set what `keyboard_state` returns, as if those keys were held down or locked
+pub fn set_keyboard_state(modifiers: Modifiers) {
+    with_state(|state| state.keyboard_state = Some(modifiers))
+}
+
+/// What's on the simulated clipboard
+pub fn clipboard() -> Option<Data> {
+    with_state(|state| state.clipboard.clone())
+}
+
+/// Put something on the simulated clipboard, as if it were copied from another application
+pub fn set_clipboard(data: Option<Data>) {
+    with_state(|state| state.clipboard = data)
+}
+
+/// Send an event to the handler given to `on_event`, as if it came from `window`
+pub fn send_event(window: WindowId, event: Event) {
+    dispatch_event(window, event)
+}
+
+/// Call the function given to `set_render`, as if `window` needed drawing
+pub fn render(window: WindowId) {
+    dispatch_render(window)
+}
+
+/// Ask to close `window`, as if the user clicked its close button. The handler is sent
+/// `CloseRequested`, and then `Exit` before the window closes, unless it vetoed that.
+/// Returns whether the window closed. When called from the handler, the request is made
+/// again once the handler returns, as with the bridge, and this returns false
+pub fn request_close(window: WindowId) -> bool {
+    let open = with_state(|state| {
+        state
+            .window(window.0)
+            .map(|w| w.close_vetoed = false)
+            .is_some()
+    });
+    if !open {
+        return false;
+    }
+    dispatch_event(window, Event::CloseRequested);
+    if with_state(|state| state.window(window.0).is_some_and(|w| w.close_vetoed)) {
+        return false;
+    }
+    close(window.0);
+    true
+}
+
+/// Close `window` the way the bridge does, sending it `Exit` first. Closing the main window
+/// closes the rest as well
+fn close(window: c_int) {
+    if !with_state(|state| state.windows.contains_key(&window)) {
+        return;
+    }
+    dispatch_event(WindowId(window), Event::Exit);
+    let others: Vec<c_int> = with_state(|state| {
+        if window == state.main_window {
+            state
+                .windows
+                .keys()
+                .copied()
+                .filter(|w| *w != window)
+                .collect()
+        } else {
+            vec![]
+        }
+    });
+    for other in others {
+        close(other);
+    }
+    with_state(|state| state.close_window(window));
+}
+
+/// The menu bar being shown in `window`
+pub fn menu_bar(window: WindowId) -> Option<MenuBarState> {
+    with_state(|state| {
+        let menu_bar = state.windows.get(&window.0)?.menu_bar?;
+        Some(state.menu_bar_state(menu_bar))
+    })
+}
+
+/// The last menu that was popped up over `window`
+pub fn popup_menu(window: WindowId) -> Option<MenuState> {
+    with_state(|state| {
+        let menu = state.windows.get(&window.0)?.popup_menu?;
+        Some(state.menu_state(menu))
+    })
+}
+
+pub(crate) fn menu_state(menu: *const c_void) -> MenuState {
+    with_state(|state| state.menu_state(menu as usize))
+}
+
+/// The sizes of the images in the icon that was set on `window`
+pub fn icon(window: WindowId) -> Option<Vec<Size>> {
+    read_window(window, |w| w.icon.clone()).flatten()
+}
+
+/// The sizes of the images in a PNG or ICO file, read from its header, or `None` if it's
+/// neither. The mock backend doesn't decode the images themselves
+fn image_sizes(bytes: &[u8]) -> Option<Vec<Size>> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") && bytes.len() >= 24 {
+        let read =
+            |i: usize| u32::from_be_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+        return Some(vec![Size {
+            width: read(16),
+            height: read(20),
+        }]);
+    }
+    // An ICO starts with 0 and 1, then the number of images, each with a 16 byte entry
+    if bytes.len() >= 6 && bytes[..4] == [0, 0, 1, 0] {
+        let count = u16::from_le_bytes([bytes[4], bytes[5]]) as usize;
+        // A dimension of 0 means 256
+        let dimension = |d: u8| if d == 0 { 256 } else { d as u32 };
+        let entries = bytes.get(6..6 + count * 16)?;
+        return Some(
+            entries
+                .chunks(16)
+                .map(|e| Size {
+                    width: dimension(e[0]),
+                    height: dimension(e[1]),
+                })
+                .collect(),
+        );
+    }
+    None
+}
+
+pub(crate) fn menu_bar_state(menu_bar: *const c_void) -> MenuBarState {
+    with_state(|state| state.menu_bar_state(menu_bar as usize))
+}
+
+/// Stand-ins for the functions exported by the bridge library
+pub(crate) mod ffi {
+    use std::ffi::CStr;
+    use std::os::raw::{c_char, c_int, c_void};
+
+    use super::{close, with_state, Call, Item, Modifiers, WindowState};
+    use crate::bridge::notsafe::{OSXHandle, WindowOptions, WindowsHandle, X11Handle};
+    use crate::bridge::{CursorType, Data, EventType, Point, Rect, Size, WheelAxis};
+    use crate::bridge::{WindowBuilder, WindowId};
+
+    unsafe fn to_string(s: *const c_char) -> String {
+        CStr::from_ptr(s).to_string_lossy().into_owned()
+    }
+
+    unsafe fn to_option_string(s: *const c_char) -> Option<String> {
+        if s.is_null() {
+            None
+        } else {
+            Some(to_string(s))
+        }
+    }
+
+    pub unsafe fn init_app(options: WindowOptions) {
+        let (title, builder) = (
+            to_string(options.title),
+            WindowBuilder::from_options(&options),
+        );
+        with_state(|state| {
+            state.main_window = state.create_window(title, &options);
+            state.calls.push(Call::InitApp(builder));
+        })
+    }
+
+    pub unsafe fn set_render(_render: extern "C" fn(window: c_int)) {
+        with_state(|state| state.calls.push(Call::SetRender))
+    }
+
+    pub unsafe fn run_app() {
+        with_state(|state| state.calls.push(Call::RunApp))
+    }
+
+    pub unsafe fn close_app() {
+        let main_window = with_state(|state| {
+            state.calls.push(Call::CloseApp);
+            state.main_window
+        });
+        close(main_window);
+    }
+
+    // Windows
+    pub unsafe fn get_main_window() -> c_int {
+        with_state(|state| state.main_window)
+    }
+
+    pub unsafe fn create_window(options: WindowOptions) -> c_int {
+        let (title, builder) = (
+            to_string(options.title),
+            WindowBuilder::from_options(&options),
+        );
+        with_state(|state| {
+            let window = state.create_window(title, &options);
+            state.calls.push(Call::CreateWindow {
+                window: WindowId(window),
+                builder,
+            });
+            window
+        })
+    }
+
+    pub unsafe fn close_window(window: c_int) {
+        with_state(|state| state.calls.push(Call::CloseWindow(WindowId(window))));
+        close(window);
+    }
+
+    pub unsafe fn request_close(window: c_int) {
+        with_state(|state| state.calls.push(Call::RequestClose(WindowId(window))));
+        super::request_close(WindowId(window));
+    }
+
+    pub unsafe fn veto_close(window: c_int) {
+        update_window(window, Call::VetoClose(WindowId(window)), |w| {
+            w.close_vetoed = true
+        })
+    }
+
+    /// Change the state of `window` if it's open, and record `call` either way
+    fn update_window(window: c_int, call: Call, f: impl FnOnce(&mut WindowState)) {
+        with_state(|state| {
+            if let Some(w) = state.window(window) {
+                f(w);
+            }
+            state.calls.push(call);
+        })
+    }
+
+    pub unsafe fn set_title(window: c_int, title: *const c_char) {
+        let title = to_string(title);
+        let call = Call::SetTitle {
+            window: WindowId(window),
+            title: title.clone(),
+        };
+        update_window(window, call, |w| w.title = title)
+    }
+
+    pub unsafe fn set_size(window: c_int, width: u32, height: u32) {
+        let call = Call::SetSize {
+            window: WindowId(window),
+            width,
+            height,
+        };
+        update_window(window, call, |w| w.client_size = (width, height))
+    }
+
+    pub unsafe fn set_position(window: c_int, position: Point) {
+        let call = Call::SetPosition {
+            window: WindowId(window),
+            position,
+        };
+        update_window(window, call, |w| w.position = Some(position))
+    }
+
+    pub unsafe fn set_visible(window: c_int, visible: bool) {
+        let call = Call::SetVisible {
+            window: WindowId(window),
+            visible,
+        };
+        update_window(window, call, |w| w.visible = visible)
+    }
+
+    pub unsafe fn set_fullscreen(window: c_int, fullscreen: bool) {
+        let call = Call::SetFullscreen {
+            window: WindowId(window),
+            fullscreen,
+        };
+        update_window(window, call, |w| w.fullscreen = fullscreen)
+    }
+
+    pub unsafe fn maximize(window: c_int) {
+        update_window(window, Call::Maximize(WindowId(window)), |w| {
+            w.maximized = true;
+            w.minimized = false;
+        })
+    }
+
+    pub unsafe fn minimize(window: c_int) {
+        update_window(window, Call::Minimize(WindowId(window)), |w| {
+            w.minimized = true
+        })
+    }
+
+    pub unsafe fn restore(window: c_int) {
+        update_window(window, Call::Restore(WindowId(window)), |w| {
+            w.maximized = false;
+            w.minimized = false;
+        })
+    }
+
+    pub unsafe fn is_fullscreen(window: c_int) -> bool {
+        with_state(|state| state.window(window).is_some_and(|w| w.fullscreen))
+    }
+
+    pub unsafe fn is_maximized(window: c_int) -> bool {
+        with_state(|state| state.window(window).is_some_and(|w| w.maximized))
+    }
+
+    pub unsafe fn is_minimized(window: c_int) -> bool {
+        with_state(|state| state.window(window).is_some_and(|w| w.minimized))
+    }
+
+    pub unsafe fn refresh(window: c_int) {
+        update_window(window, Call::Refresh(WindowId(window)), |_| ())
+    }
+
+    pub unsafe fn get_client_size(window: c_int) -> Size {
+        with_state(|state| {
+            let (width, height) = state.window(window).map_or((0, 0), |w| w.client_size);
+            Size { width, height }
+        })
+    }
+
+    pub unsafe fn get_display_size(window: c_int) -> Size {
+        get_client_size(window)
+    }
+
+    pub unsafe fn get_scale_factor(_window: c_int) -> f32 {
+        1.0
+    }
+
+    #[allow(dead_code)]
+    pub unsafe fn get_windows_raw_window_handle(_window: c_int) -> WindowsHandle {
+        WindowsHandle {
+            hwnd: std::ptr::null_mut(),
+            hinstance: std::ptr::null_mut(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub unsafe fn get_osx_raw_window_handle(_window: c_int) -> OSXHandle {
+        OSXHandle {
+            ns_window: std::ptr::null_mut(),
+            ns_view: std::ptr::null_mut(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub unsafe fn is_wayland() -> bool {
+        false
+    }
+
+    #[allow(dead_code)]
+    pub unsafe fn get_x11_raw_window_handle(_window: c_int) -> X11Handle {
+        X11Handle {
+            window: 0,
+            display: std::ptr::null_mut(),
+            screen: 0,
+        }
+    }
+
+    // Events. The mock backend never generates raw events, see `send_event` instead
+    pub unsafe fn bind_canvas_events(
+        _handle_event: extern "C" fn(window: c_int, event: *const c_void),
+    ) {
+        with_state(|state| state.calls.push(Call::BindCanvasEvents))
+    }
+
+    pub unsafe fn get_event_type(_event: *const c_void) -> EventType {
+        EventType::Unknown
+    }
+
+    pub unsafe fn get_event_key(_key_event: *const c_void) -> c_int {
+        0
+    }
+
+    pub unsafe fn get_raw_key_code(_key_event: *const c_void) -> u32 {
+        0
+    }
+
+    pub unsafe fn get_native_scan_code(_key_event: *const c_void) -> u32 {
+        0
+    }
+
+    pub unsafe fn is_auto_repeat(_key_event: *const c_void) -> bool {
+        false
+    }
+
+    pub unsafe fn get_event_char(_key_event: *const c_void) -> u32 {
+        0
+    }
+
+    pub unsafe fn get_modifiers(_key_event: *const c_void) -> u32 {
+        0
+    }
+
+    pub unsafe fn get_mouse_modifiers(_mouse_event: *const c_void) -> u32 {
+        0
+    }
+
+    pub unsafe fn get_keyboard_state() -> u32 {
+        with_state(|state| state.keyboard_state.unwrap_or(Modifiers::NONE).bits())
+    }
+
+    pub unsafe fn get_move_position(_move_event: *const c_void) -> Point {
+        Point { x: 0, y: 0 }
+    }
+
+    pub unsafe fn get_event_focused(_focus_event: *const c_void) -> bool {
+        false
+    }
+
+    pub unsafe fn get_event_iconized(_iconize_event: *const c_void) -> bool {
+        false
+    }
+
+    pub unsafe fn get_event_fullscreen(_fullscreen_event: *const c_void) -> bool {
+        false
+    }
+
+    pub unsafe fn get_event_id(_menu_event: *const c_void) -> i32 {
+        0
+    }
+
+    pub unsafe fn get_mouse_position(_mouse_event: *const c_void) -> Point {
+        Point { x: 0, y: 0 }
+    }
+
+    pub unsafe fn get_relative_motion(_relative_event: *const c_void) -> Point {
+        Point { x: 0, y: 0 }
+    }
+
+    pub unsafe fn get_gesture_position(_gesture_event: *const c_void) -> Point {
+        Point { x: 0, y: 0 }
+    }
+
+    pub unsafe fn is_gesture_start(_gesture_event: *const c_void) -> bool {
+        false
+    }
+
+    pub unsafe fn is_gesture_end(_gesture_event: *const c_void) -> bool {
+        false
+    }
+
+    pub unsafe fn get_pan_delta(_pan_event: *const c_void) -> Point {
+        Point { x: 0, y: 0 }
+    }
+
+    pub unsafe fn get_zoom_factor(_zoom_event: *const c_void) -> f32 {
+        1.0
+    }
+
+    pub unsafe fn get_rotation_angle(_rotate_event: *const c_void) -> f32 {
+        0.0
+    }
+
+    pub unsafe fn get_mouse_wheel_rotation(_mouse_event: *const c_void) -> c_int {
+        0
+    }
+
+    pub unsafe fn get_mouse_wheel_delta(_mouse_event: *const c_void) -> c_int {
+        0
+    }
+
+    pub unsafe fn get_mouse_wheel_axis(_mouse_event: *const c_void) -> WheelAxis {
+        WheelAxis::Vertical
+    }
+
+    pub unsafe fn get_mouse_wheel_lines_per_action(_mouse_event: *const c_void) -> c_int {
+        0
+    }
+
+    pub unsafe fn get_mouse_wheel_columns_per_action(_mouse_event: *const c_void) -> c_int {
+        0
+    }
+
+    pub unsafe fn is_mouse_wheel_inverted(_mouse_event: *const c_void) -> bool {
+        false
+    }
+
+    pub unsafe fn is_mouse_wheel_precise(_mouse_event: *const c_void) -> bool {
+        false
+    }
+
+    pub unsafe fn get_magnification(_mouse_event: *const c_void) -> f32 {
+        0.0
+    }
+
+    pub unsafe fn get_ime_text_len(_ime_event: *const c_void) -> c_int {
+        0
+    }
+
+    pub unsafe fn get_ime_text(_ime_event: *const c_void, _text: *mut u8) {}
+
+    pub unsafe fn get_ime_cursor(_ime_event: *const c_void) -> c_int {
+        -1
+    }
+
+    // Mouse
+    pub unsafe fn capture_mouse(window: c_int) {
+        update_window(window, Call::CaptureMouse(WindowId(window)), |w| {
+            w.mouse_captured = true
+        })
+    }
+
+    pub unsafe fn release_mouse(window: c_int) {
+        update_window(window, Call::ReleaseMouse(WindowId(window)), |w| {
+            w.mouse_captured = false
+        })
+    }
+
+    pub unsafe fn set_relative_mouse_mode(window: c_int, enabled: bool) {
+        let call = Call::SetRelativeMouseMode {
+            window: WindowId(window),
+            enabled,
+        };
+        update_window(window, call, |w| w.relative_mouse_mode = enabled)
+    }
+
+    pub unsafe fn get_pointer_position(window: c_int) -> Point {
+        with_state(|state| {
+            state
+                .window(window)
+                .and_then(|w| w.pointer_position)
+                .unwrap_or(Point { x: 0, y: 0 })
+        })
+    }
+
+    pub unsafe fn warp_pointer(window: c_int, position: Point) {
+        let call = Call::WarpPointer {
+            window: WindowId(window),
+            position,
+        };
+        update_window(window, call, |w| w.pointer_position = Some(position))
+    }
+
+    // Input methods
+    pub unsafe fn set_ime_cursor_area(window: c_int, area: Rect) {
+        let call = Call::SetImeCursorArea {
+            window: WindowId(window),
+            area,
+        };
+        update_window(window, call, |w| w.ime_cursor_area = Some(area))
+    }
+
+    pub unsafe fn set_ime_allowed(window: c_int, allowed: bool) {
+        let call = Call::SetImeAllowed {
+            window: WindowId(window),
+            allowed,
+        };
+        update_window(window, call, |w| w.ime_allowed = Some(allowed))
+    }
+
+    // Cursor
+    pub unsafe fn set_cursor(window: c_int, cursor: CursorType) {
+        let call = Call::SetCursor {
+            window: WindowId(window),
+            cursor,
+        };
+        update_window(window, call, |w| w.cursor = Some(cursor))
+    }
+
+    // Status Bar
+    pub unsafe fn set_status_text(window: c_int, text: *const c_char) {
+        let text = to_string(text);
+        let call = Call::SetStatusText {
+            window: WindowId(window),
+            text: text.clone(),
+        };
+        update_window(window, call, |w| {
+            if w.status_bar {
+                w.status_text = Some(text);
+            }
+        })
+    }
+
+    pub unsafe fn create_status_bar(window: c_int) {
+        update_window(window, Call::CreateStatusBar(WindowId(window)), |w| {
+            w.status_bar = true
+        })
+    }
+
+    // Clipboard
+    pub unsafe fn put_string_on_clipboard(string: *const c_char) {
+        let data = Data::String(to_string(string));
+        with_state(|state| {
+            state.clipboard = Some(data.clone());
+            state.calls.push(Call::PutOnClipboard(data));
+        })
+    }
+
+    pub unsafe fn put_buffer_on_clipboard(buf: *const u8, len: u32) {
+        let data = Data::Custom(std::slice::from_raw_parts(buf, len as usize).to_vec());
+        with_state(|state| {
+            state.clipboard = Some(data.clone());
+            state.calls.push(Call::PutOnClipboard(data));
+        })
+    }
+
+    pub unsafe fn get_string_from_clipboard(string: *mut u8) {
+        if let Some(Data::String(s)) = super::clipboard() {
+            std::ptr::copy_nonoverlapping(s.as_ptr(), string, s.len());
+        }
+    }
+
+    pub unsafe fn get_clipboard_string_len() -> i32 {
+        match super::clipboard() {
+            Some(Data::String(s)) => s.len() as i32,
+            _ => -1,
+        }
+    }
+
+    pub unsafe fn get_buffer_from_clipboard(buff: *mut u8) {
+        if let Some(Data::Custom(b)) = super::clipboard() {
+            std::ptr::copy_nonoverlapping(b.as_ptr(), buff, b.len());
+        }
+    }
+
+    pub unsafe fn get_clipboard_buffer_len() -> i32 {
+        match super::clipboard() {
+            Some(Data::Custom(b)) => b.len() as i32,
+            _ => -1,
+        }
+    }
+
+    // Menus
+    pub unsafe fn create_menu() -> *const c_void {
+        with_state(|state| {
+            let menu = state.new_handle();
+            state.menus.insert(menu, vec![]);
+            state.calls.push(Call::CreateMenu(menu));
+            menu as *const c_void
+        })
+    }
+
+    pub unsafe fn insert_to_menu(
+        menu: *const c_void,
+        i: u32,
+        entry: *const c_char,
+        help: *const c_char,
+    ) -> i32 {
+        let (entry, help) = (to_string(entry), to_option_string(help));
+        with_state(|state| {
+            let (menu, i) = (menu as usize, i as usize);
+            state.calls.push(Call::InsertToMenu {
+                menu,
+                i,
+                entry: entry.clone(),
+                help: help.clone(),
+            });
+            state.next_event_id += 1;
+            let event_id = state.next_event_id;
+            if let Some(items) = state.menus.get_mut(&menu).filter(|items| i <= items.len()) {
+                items.insert(
+                    i,
+                    Item::Entry {
+                        event_id,
+                        label: entry,
+                        help,
+                        enabled: true,
+                    },
+                );
+            }
+            event_id
+        })
+    }
+
+    pub unsafe fn insert_submenu(
+        menu: *const c_void,
+        i: u32,
+        submenu: *const c_void,
+        entry: *const c_char,
+        help: *const c_char,
+    ) {
+        let (entry, help) = (to_string(entry), to_option_string(help));
+        with_state(|state| {
+            let (menu, i, submenu) = (menu as usize, i as usize, submenu as usize);
+            state.calls.push(Call::InsertSubmenu {
+                menu,
+                i,
+                submenu,
+                entry: entry.clone(),
+                help: help.clone(),
+            });
+            if let Some(items) = state.menus.get_mut(&menu).filter(|items| i <= items.len()) {
+                items.insert(
+                    i,
+                    Item::Submenu {
+                        submenu,
+                        label: entry,
+                        help,
+                    },
+                );
+            }
+        })
+    }
+
+    pub unsafe fn insert_separator_to_menu(menu: *const c_void, i: u32) {
+        with_state(|state| {
+            let (menu, i) = (menu as usize, i as usize);
+            state.calls.push(Call::InsertSeparatorToMenu { menu, i });
+            if let Some(items) = state.menus.get_mut(&menu).filter(|items| i <= items.len()) {
+                items.insert(i, Item::Separator);
+            }
+        })
+    }
+
+    pub unsafe fn remove_from_menu(menu: *const c_void, i: u32) {
+        with_state(|state| {
+            let (menu, i) = (menu as usize, i as usize);
+            state.calls.push(Call::RemoveFromMenu { menu, i });
+            if let Some(items) = state.menus.get_mut(&menu).filter(|items| i < items.len()) {
+                items.remove(i);
+            }
+        })
+    }
+
+    pub unsafe fn enable_menu_item(menu: *const c_void, i: u32, enable: bool) {
+        with_state(|state| {
+            let (menu, i) = (menu as usize, i as usize);
+            state.calls.push(Call::EnableMenuItem { menu, i, enable });
+            if let Some(Item::Entry { enabled, .. }) = state
+                .menus
+                .get_mut(&menu)
+                .and_then(|items| items.get_mut(i))
+            {
+                *enabled = enable;
+            }
+        })
+    }
+
+    pub unsafe fn set_status_menu(window: c_int, menu: *const c_void) {
+        let menu = menu as usize;
+        let call = Call::PopupMenu {
+            window: WindowId(window),
+            menu,
+        };
+        update_window(window, call, |w| w.popup_menu = Some(menu))
+    }
+
+    pub unsafe fn delete_menu(menu: *const c_void) {
+        with_state(|state| {
+            state.menus.remove(&(menu as usize));
+            for w in state.windows.values_mut() {
+                if w.popup_menu == Some(menu as usize) {
+                    w.popup_menu = None;
+                }
+            }
+            state.calls.push(Call::DeleteMenu(menu as usize));
+        })
+    }
+
+    pub unsafe fn create_menu_bar() -> *const c_void {
+        with_state(|state| {
+            let menu_bar = state.new_handle();
+            state.menu_bars.insert(menu_bar, vec![]);
+            state.calls.push(Call::CreateMenuBar(menu_bar));
+            menu_bar as *const c_void
+        })
+    }
+
+    pub unsafe fn insert_to_menu_bar(
+        menu_bar: *const c_void,
+        menu: *const c_void,
+        i: u32,
+        entry: *const c_char,
+    ) {
+        let entry = to_string(entry);
+        with_state(|state| {
+            let (menu_bar, menu, i) = (menu_bar as usize, menu as usize, i as usize);
+            state.calls.push(Call::InsertToMenuBar {
+                menu_bar,
+                menu,
+                i,
+                entry: entry.clone(),
+            });
+            if let Some(menus) = state
+                .menu_bars
+                .get_mut(&menu_bar)
+                .filter(|menus| i <= menus.len())
+            {
+                menus.insert(i, (menu, entry));
+            }
+        })
+    }
+
+    pub unsafe fn remove_from_menu_bar(menu_bar: *const c_void, i: u32) {
+        with_state(|state| {
+            let (menu_bar, i) = (menu_bar as usize, i as usize);
+            state.calls.push(Call::RemoveFromMenuBar { menu_bar, i });
+            if let Some(menus) = state
+                .menu_bars
+                .get_mut(&menu_bar)
+                .filter(|menus| i < menus.len())
+            {
+                menus.remove(i);
+            }
+        })
+    }
+
+    pub unsafe fn set_menu_bar(window: c_int, menu_bar: *const c_void) {
+        let menu_bar = menu_bar as usize;
+        with_state(|state| {
+            // A menu bar can only be in one window at a time
+            if state.windows.contains_key(&window) {
+                for w in state.windows.values_mut() {
+                    if w.menu_bar == Some(menu_bar) {
+                        w.menu_bar = None;
+                    }
+                }
+            }
+            if let Some(w) = state.window(window) {
+                w.menu_bar = Some(menu_bar);
+            }
+            state.calls.push(Call::SetMenuBar {
+                window: WindowId(window),
+                menu_bar,
+            });
+        })
+    }
+
+    pub unsafe fn delete_menu_bar(menu_bar: *const c_void) {
+        with_state(|state| {
+            state.menu_bars.remove(&(menu_bar as usize));
+            for w in state.windows.values_mut() {
+                if w.menu_bar == Some(menu_bar as usize) {
+                    w.menu_bar = None;
+                }
+            }
+            state.calls.push(Call::DeleteMenuBar(menu_bar as usize));
+        })
+    }
+
+    // Icons
+    pub unsafe fn create_icon_bundle() -> *const c_void {
+        with_state(|state| {
+            let icon = state.new_handle();
+            state.icons.insert(icon, vec![]);
+            state.calls.push(Call::CreateIcon(icon));
+            icon as *const c_void
+        })
+    }
+
+    pub unsafe fn add_rgba_to_icon_bundle(
+        bundle: *const c_void,
+        width: u32,
+        height: u32,
+        _rgba: *const u8,
+    ) {
+        with_state(|state| {
+            let icon = bundle as usize;
+            if let Some(sizes) = state.icons.get_mut(&icon) {
+                sizes.push(Size { width, height });
+            }
+            state.calls.push(Call::AddRgbaToIcon {
+                icon,
+                width,
+                height,
+            });
+        })
+    }
+
+    pub unsafe fn add_file_to_icon_bundle(
+        bundle: *const c_void,
+        bytes: *const u8,
+        len: u32,
+    ) -> bool {
+        let images = super::image_sizes(std::slice::from_raw_parts(bytes, len as usize));
+        with_state(|state| {
+            let icon = bundle as usize;
+            state.calls.push(Call::AddFileToIcon(icon));
+            match (state.icons.get_mut(&icon), images) {
+                (Some(sizes), Some(images)) if !images.is_empty() => {
+                    sizes.extend(images);
+                    true
+                }
+                _ => false,
+            }
+        })
+    }
+
+    pub unsafe fn get_icon_bundle_count(bundle: *const c_void) -> u32 {
+        with_state(|state| {
+            state
+                .icons
+                .get(&(bundle as usize))
+                .map_or(0, |s| s.len() as u32)
+        })
+    }
+
+    pub unsafe fn get_icon_bundle_size(bundle: *const c_void, i: u32) -> Size {
+        with_state(|state| {
+            state
+                .icons
+                .get(&(bundle as usize))
+                .and_then(|sizes| sizes.get(i as usize).copied())
+                .unwrap_or(Size {
+                    width: 0,
+                    height: 0,
+                })
+        })
+    }
+
+    pub unsafe fn delete_icon_bundle(bundle: *const c_void) {
+        with_state(|state| {
+            state.icons.remove(&(bundle as usize));
+            state.calls.push(Call::DeleteIcon(bundle as usize));
+        })
+    }
+
+    pub unsafe fn set_icon(window: c_int, bundle: *const c_void) {
+        let icon = bundle as usize;
+        with_state(|state| {
+            // Windows keep their own copy of the icon
+            let sizes = state.icons.get(&icon).cloned();
+            if let Some(w) = state.window(window) {
+                w.icon = sizes;
+            }
+            state.calls.push(Call::SetIcon {
+                window: WindowId(window),
+                icon,
+            });
+        })
+    }
+
+    // Every component can be used with the mock
+    #[cfg(feature = "aui")]
+    pub unsafe fn init_aui() -> bool {
+        true
+    }
+
+    #[cfg(feature = "opengl")]
+    pub unsafe fn init_gl() -> bool {
+        true
+    }
+
+    #[cfg(feature = "html")]
+    pub unsafe fn init_html() -> bool {
+        true
+    }
+
+    #[cfg(feature = "propgrid")]
+    pub unsafe fn init_propgrid() -> bool {
+        true
+    }
+
+    #[cfg(feature = "ribbon")]
+    pub unsafe fn init_ribbon() -> bool {
+        true
+    }
+
+    #[cfg(feature = "richtext")]
+    pub unsafe fn init_richtext() -> bool {
+        true
+    }
+
+    #[cfg(feature = "stc")]
+    pub unsafe fn init_stc() -> bool {
+        true
+    }
+
+    #[cfg(feature = "xrc")]
+    pub unsafe fn init_xrc() -> bool {
+        true
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::{
+        close_app, get_from_clipboard, init_app, on_event, put_on_clipboard, Icon, Menu, MenuEntry,
+        MenuItem, Window, WindowBuilder,
+    };
+
+    /// Start a fresh app whose handler records every event, and calls `react` with each
+    fn record(react: impl Fn(&Event) + 'static) -> Rc<RefCell<Vec<(WindowId, Event)>>> {
+        reset();
+        init_app("test", 320, 240);
+        let events = Rc::new(RefCell::new(vec![]));
+        let recorded = events.clone();
+        on_event(move |window, event| {
+            react(&event);
+            recorded.borrow_mut().push((window, event));
+        });
+        events
+    }
+
+    #[test]
+    fn events_sent_from_the_handler_are_delivered() {
+        let events = record(|event| {
+            if *event == Event::MouseEnter {
+                send_event(Window::main().id(), Event::MouseLeave);
+            }
+        });
+        let main = Window::main().id();
+        send_event(main, Event::MouseEnter);
+        assert_eq!(
+            *events.borrow(),
+            [(main, Event::MouseEnter), (main, Event::MouseLeave)]
+        );
+    }
+
+    #[test]
+    fn reset_forgets_the_handler() {
+        let events = record(|_| {});
+        reset();
+        init_app("test", 320, 240);
+        send_event(Window::main().id(), Event::MouseEnter);
+        assert!(events.borrow().is_empty());
+    }
+
+    #[test]
+    fn repeated_events_are_coalesced_while_the_handler_runs() {
+        let events = record(|event| {
+            if *event == Event::MouseEnter {
+                let main = Window::main().id();
+                for width in [100, 200] {
+                    send_event(main, Event::Timer);
+                    send_event(main, Event::Resized(Size { width, height: 50 }));
+                }
+                send_event(main, Event::MouseLeave);
+            }
+        });
+        let main = Window::main().id();
+        send_event(main, Event::MouseEnter);
+        assert_eq!(
+            *events.borrow(),
+            [
+                (main, Event::MouseEnter),
+                (main, Event::Timer),
+                (
+                    main,
+                    Event::Resized(Size {
+                        width: 200,
+                        height: 50
+                    })
+                ),
+                (main, Event::MouseLeave)
+            ]
+        );
+    }
+
+    #[test]
+    fn closing_from_the_handler_sends_exit() {
+        let events = record(|event| {
+            if *event == Event::MouseEnter {
+                close_app();
+            }
+        });
+        let main = Window::main().id();
+        let other = Window::new(WindowBuilder::new("other")).id();
+        send_event(main, Event::MouseEnter);
+        assert_eq!(
+            *events.borrow(),
+            [
+                (main, Event::MouseEnter),
+                (main, Event::Exit),
+                (other, Event::Exit)
+            ]
+        );
+        assert!(windows().is_empty());
+    }
+
+    #[test]
+    fn closing_a_window_sends_it_exit() {
+        let events = record(|_| {});
+        let main = Window::main().id();
+        let other = Window::new(WindowBuilder::new("other"));
+        other.close();
+        assert_eq!(*events.borrow(), [(other.id(), Event::Exit)]);
+        assert_eq!(windows(), [main]);
+    }
+
+    #[test]
+    fn close_requests_can_be_vetoed() {
+        let events = record(|event| {
+            if *event == Event::CloseRequested {
+                Window::main().veto_close();
+            }
+        });
+        let main = Window::main().id();
+        assert!(!request_close(main));
+        assert_eq!(*events.borrow(), [(main, Event::CloseRequested)]);
+        assert_eq!(windows(), [main]);
+    }
+
+    #[test]
+    fn close_requests_from_the_handler_are_asked_again_after_it() {
+        let events = record(|event| {
+            if *event == Event::MouseEnter {
+                assert!(!request_close(Window::main().id()));
+            }
+        });
+        let main = Window::main().id();
+        send_event(main, Event::MouseEnter);
+        assert_eq!(
+            *events.borrow(),
+            [
+                (main, Event::MouseEnter),
+                (main, Event::CloseRequested),
+                (main, Event::Exit)
+            ]
+        );
+        assert!(windows().is_empty());
+    }
+
+    #[test]
+    fn close_requests_from_the_handler_can_be_vetoed() {
+        let events = record(|event| match event {
+            Event::MouseEnter => {
+                request_close(Window::main().id());
+            }
+            Event::CloseRequested => Window::main().veto_close(),
+            _ => (),
+        });
+        let main = Window::main().id();
+        send_event(main, Event::MouseEnter);
+        assert_eq!(
+            *events.borrow(),
+            [(main, Event::MouseEnter), (main, Event::CloseRequested)]
+        );
+        assert_eq!(windows(), [main]);
+    }
+
+    #[test]
+    fn close_requests_that_arent_vetoed_exit() {
+        let events = record(|_| {});
+        let main = Window::main().id();
+        assert!(request_close(main));
+        assert_eq!(
+            *events.borrow(),
+            [(main, Event::CloseRequested), (main, Event::Exit)]
+        );
+        assert!(windows().is_empty());
+    }
+
+    #[test]
+    fn status_text_can_be_read_back() {
+        record(|_| {});
+        let main = Window::main();
+        main.create_status_bar();
+        main.set_status_text("Ready");
+        assert_eq!(status_text(main.id()).as_deref(), Some("Ready"));
+    }
+
+    #[test]
+    fn menu_items_can_be_inserted_enabled_and_removed() {
+        reset();
+        let mut menu = Menu::new(None)
+            .push_entry(MenuEntry::new(1, "Open".to_string()).help("Open a file".to_string()))
+            .push_separator();
+        let event_id = menu_event_id(&menu);
+        let entry = |enabled| MenuItemState::Entry {
+            event_id,
+            label: "Open".to_string(),
+            help: Some("Open a file".to_string()),
+            enabled,
+        };
+        assert_eq!(
+            menu.mock_state().items,
+            [entry(true), MenuItemState::Separator]
+        );
+
+        menu.disable_item_by_id(1);
+        assert_eq!(
+            menu.mock_state().items,
+            [entry(false), MenuItemState::Separator]
+        );
+        menu.enable_item_by_id(1);
+        assert_eq!(
+            menu.mock_state().items,
+            [entry(true), MenuItemState::Separator]
+        );
+
+        menu.insert(1, MenuItem::Separator);
+        assert_eq!(menu.mock_state().items.len(), 3);
+        menu.remove(0);
+        assert_eq!(
+            menu.mock_state().items,
+            [MenuItemState::Separator, MenuItemState::Separator]
+        );
+    }
+
+    /// The event id that the first item of `menu` was given
+    fn menu_event_id(menu: &Menu<i32>) -> i32 {
+        match menu.mock_state().items.first() {
+            Some(MenuItemState::Entry { event_id, .. }) => *event_id,
+            item => panic!("expected an entry, found {:?}", item),
+        }
+    }
+
+    #[test]
+    fn clipboard_round_trips() {
+        reset();
+        assert_eq!(get_from_clipboard(), None);
+        put_on_clipboard(&Data::String("hello".to_string()));
+        assert_eq!(
+            get_from_clipboard(),
+            Some(Data::String("hello".to_string()))
+        );
+        put_on_clipboard(&Data::Custom(vec![1, 2, 3]));
+        assert_eq!(clipboard(), Some(Data::Custom(vec![1, 2, 3])));
+        assert_eq!(get_from_clipboard(), Some(Data::Custom(vec![1, 2, 3])));
+    }
+
+    #[test]
+    #[should_panic(expected = "icon images can't be empty")]
+    fn empty_icon_images_are_rejected() {
+        reset();
+        Icon::from_rgba(0, 4, &[]);
+    }
+}